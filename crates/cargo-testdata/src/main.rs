@@ -0,0 +1,214 @@
+//! `cargo testdata review`: accepts or rejects the snapshots written with `UPDATE_SNAPSHOTS=review`.
+
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use testdata::PendingSnapshot;
+
+const USAGE: &str = "\
+Usage: cargo testdata review [OPTIONS] [DIR]
+
+Reviews the pending snapshots (*.new) under DIR, which defaults to the current directory.
+Run the tests with UPDATE_SNAPSHOTS=review to write them.
+
+Options:
+    --accept-all    Accept all pending snapshots
+    --reject-all    Reject all pending snapshots
+    --list          List pending snapshots without reviewing
+    -h, --help      Print this message";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Interactive,
+    AcceptAll,
+    RejectAll,
+    List,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    Accept,
+    Reject,
+    Skip,
+}
+
+#[derive(Debug)]
+struct Args {
+    action: Action,
+    dir: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    match review(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parses the arguments, returning `None` for `--help`.
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut args = args.peekable();
+    // Invoked as `cargo testdata ...`, cargo passes `testdata` first
+    if args.peek().map(String::as_str) == Some("testdata") {
+        args.next();
+    }
+    match args.next().as_deref() {
+        Some("review") => {}
+        Some("-h" | "--help") => return Ok(None),
+        Some(command) => return Err(format!("Unknown command: {}", command)),
+        None => return Err(String::from("Missing command")),
+    }
+
+    let mut action = Action::Interactive;
+    let mut dir = None;
+    for arg in args {
+        let new_action = match &arg[..] {
+            "-h" | "--help" => return Ok(None),
+            "--accept-all" => Action::AcceptAll,
+            "--reject-all" => Action::RejectAll,
+            "--list" => Action::List,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+            _ if dir.is_none() => {
+                dir = Some(PathBuf::from(arg));
+                continue;
+            }
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        };
+        if action != Action::Interactive {
+            return Err(String::from(
+                "--accept-all, --reject-all and --list are exclusive",
+            ));
+        }
+        action = new_action;
+    }
+    Ok(Some(Args {
+        action,
+        dir: dir.unwrap_or_else(|| PathBuf::from(".")),
+    }))
+}
+
+fn review(args: &Args) -> io::Result<()> {
+    let pending = PendingSnapshot::find(&args.dir)?;
+    if pending.is_empty() {
+        println!("No pending snapshots");
+        return Ok(());
+    }
+    if args.action == Action::List {
+        for snapshot in &pending {
+            println!("{}", snapshot.target.display());
+        }
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut bulk = match args.action {
+        Action::AcceptAll => Some(Decision::Accept),
+        Action::RejectAll => Some(Decision::Reject),
+        _ => None,
+    };
+    let (mut accepted, mut rejected, mut skipped) = (0, 0, 0);
+    for (i, snapshot) in pending.iter().enumerate() {
+        let decision = if let Some(decision) = bulk {
+            decision
+        } else {
+            println!(
+                "[{}/{}] {}",
+                i + 1,
+                pending.len(),
+                snapshot.target.display()
+            );
+            println!("{}", snapshot.diff()?.trim_end());
+            loop {
+                print!("[a]ccept, [r]eject, [s]kip, [A]ccept all, [R]eject all, [q]uit: ");
+                io::stdout().flush()?;
+                let answer = match lines.next() {
+                    Some(line) => line?,
+                    None => String::from("q"),
+                };
+                match answer.trim() {
+                    "a" => break Decision::Accept,
+                    "r" => break Decision::Reject,
+                    "s" => break Decision::Skip,
+                    "A" => {
+                        bulk = Some(Decision::Accept);
+                        break Decision::Accept;
+                    }
+                    "R" => {
+                        bulk = Some(Decision::Reject);
+                        break Decision::Reject;
+                    }
+                    "q" => {
+                        skipped += pending.len() - i;
+                        print_summary(accepted, rejected, skipped);
+                        return Ok(());
+                    }
+                    _ => continue,
+                }
+            }
+        };
+        match decision {
+            Decision::Accept => {
+                snapshot.accept()?;
+                accepted += 1;
+            }
+            Decision::Reject => {
+                snapshot.reject()?;
+                rejected += 1;
+            }
+            Decision::Skip => skipped += 1,
+        }
+    }
+    print_summary(accepted, rejected, skipped);
+    Ok(())
+}
+
+fn print_summary(accepted: usize, rejected: usize, skipped: usize) {
+    println!(
+        "Accepted {}, rejected {}, skipped {}",
+        accepted, rejected, skipped
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Args>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = parse(&["testdata", "review"]).unwrap().unwrap();
+        assert_eq!(args.action, Action::Interactive);
+        assert_eq!(args.dir, PathBuf::from("."));
+
+        let args = parse(&["review", "--accept-all", "tests"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(args.action, Action::AcceptAll);
+        assert_eq!(args.dir, PathBuf::from("tests"));
+
+        assert!(parse(&["testdata", "--help"]).unwrap().is_none());
+        assert!(parse(&["review", "--accept-all", "--reject-all"]).is_err());
+        assert!(parse(&["review", "a", "b"]).is_err());
+        assert!(parse(&["accept"]).is_err());
+    }
+}
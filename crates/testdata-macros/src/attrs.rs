@@ -3,11 +3,13 @@ use quote::ToTokens;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::{parse, Attribute, FnArg, Lit, Meta, NestedMeta, Token};
+use testdata_rt::Compression;
 
 #[derive(Debug, Clone)]
 pub(crate) struct MacroArgs {
     pub(crate) rebuild: Option<String>,
     pub(crate) root: Option<String>,
+    pub(crate) lock: Option<String>,
 }
 
 impl MacroArgs {
@@ -19,53 +21,107 @@ impl MacroArgs {
     pub(crate) fn parse_meta(meta: &Punctuated<NestedMeta, Token![,]>) -> Result<Self, syn::Error> {
         let mut rebuild = None;
         let mut root = None;
+        let mut lock = None;
         for arg in meta {
-            if let NestedMeta::Meta(arg) = arg {
-                if arg.path().is_ident("root") {
-                    if root.is_some() {
-                        return Err(syn::Error::new(arg.path().span(), "duplicate argument"));
-                    }
-                    if let Meta::NameValue(arg) = arg {
-                        if let Lit::Str(lit) = &arg.lit {
-                            root = Some(lit.value());
-                            continue;
-                        } else {
-                            return Err(syn::Error::new(arg.lit.span(), "invalid argument value"));
-                        }
-                    } else {
-                        return Err(syn::Error::new(arg.span(), "invalid argument value"));
-                    }
-                } else if arg.path().is_ident("rebuild") {
-                    if rebuild.is_some() {
-                        return Err(syn::Error::new(arg.path().span(), "duplicate argument"));
-                    }
-                    if let Meta::NameValue(arg) = arg {
-                        if let Lit::Str(lit) = &arg.lit {
-                            rebuild = Some(lit.value());
-                            continue;
-                        } else {
-                            return Err(syn::Error::new(arg.lit.span(), "invalid argument value"));
-                        }
-                    } else {
-                        return Err(syn::Error::new(arg.span(), "invalid argument value"));
-                    }
-                } else {
-                    return Err(syn::Error::new(
-                        arg.path().span(),
-                        format_args!("unknown argument: {}", arg.path().to_token_stream()),
-                    ));
-                }
+            let arg = meta_arg(arg)?;
+            let slot = if arg.path().is_ident("root") {
+                &mut root
+            } else if arg.path().is_ident("rebuild") {
+                &mut rebuild
+            } else if arg.path().is_ident("lock") {
+                &mut lock
+            } else {
+                return Err(unknown_argument(arg));
+            };
+            parse_str_arg(arg, slot)?;
+        }
+        Ok(MacroArgs {
+            rebuild,
+            root,
+            lock,
+        })
+    }
+}
+
+/// Returns the `name = value` argument, rejecting a bare literal.
+fn meta_arg(arg: &NestedMeta) -> Result<&Meta, syn::Error> {
+    if let NestedMeta::Meta(arg) = arg {
+        Ok(arg)
+    } else {
+        Err(syn::Error::new(arg.span(), "invalid argument"))
+    }
+}
+
+fn unknown_argument(arg: &Meta) -> syn::Error {
+    syn::Error::new(
+        arg.path().span(),
+        format_args!("unknown argument: {}", arg.path().to_token_stream()),
+    )
+}
+
+/// Reads the string value of the argument into the slot, rejecting duplicates.
+fn parse_str_arg(arg: &Meta, slot: &mut Option<String>) -> Result<(), syn::Error> {
+    if slot.is_some() {
+        return Err(syn::Error::new(arg.path().span(), "duplicate argument"));
+    }
+    if let Meta::NameValue(arg) = arg {
+        if let Lit::Str(lit) = &arg.lit {
+            *slot = Some(lit.value());
+            Ok(())
+        } else {
+            Err(syn::Error::new(arg.lit.span(), "invalid argument value"))
+        }
+    } else {
+        Err(syn::Error::new(arg.span(), "invalid argument value"))
+    }
+}
+
+#[cfg(feature = "json")]
+#[derive(Debug, Clone)]
+pub(crate) struct RecordsArgs {
+    pub(crate) file: String,
+    pub(crate) key: Option<String>,
+    pub(crate) rebuild: Option<String>,
+}
+
+#[cfg(feature = "json")]
+impl RecordsArgs {
+    pub(crate) fn parse(raw: TokenStream) -> Result<Self, syn::Error> {
+        let meta = parse::Parser::parse2(Punctuated::parse_terminated, raw)?;
+        Self::parse_meta(&meta)
+    }
+
+    pub(crate) fn parse_meta(meta: &Punctuated<NestedMeta, Token![,]>) -> Result<Self, syn::Error> {
+        let mut file = None;
+        let mut key = None;
+        let mut rebuild = None;
+        for arg in meta {
+            let arg = meta_arg(arg)?;
+            let slot = if arg.path().is_ident("file") {
+                &mut file
+            } else if arg.path().is_ident("key") {
+                &mut key
+            } else if arg.path().is_ident("rebuild") {
+                &mut rebuild
             } else {
-                return Err(syn::Error::new(arg.span(), "invalid argument"));
+                return Err(unknown_argument(arg));
             };
+            parse_str_arg(arg, slot)?;
         }
-        Ok(MacroArgs { rebuild, root })
+        let file = file.ok_or_else(|| {
+            syn::Error::new(Span::call_site(), "Missing argument: file = \"...\"")
+        })?;
+        Ok(RecordsArgs { file, key, rebuild })
     }
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct ArgAttrs {
     pub(crate) glob: String,
+    pub(crate) compress: Option<Compression>,
+    pub(crate) input: bool,
+    pub(crate) block: Option<usize>,
+    pub(crate) part: Option<usize>,
 }
 
 impl ArgAttrs {
@@ -78,6 +134,10 @@ impl ArgAttrs {
     }
     pub(crate) fn parse_attrs(attrs: &[Attribute], span: Span) -> Result<Self, syn::Error> {
         let mut glob = None;
+        let mut compress = None;
+        let mut input = false;
+        let mut block = None;
+        let mut part = None;
         for attr in attrs {
             let meta = if let Ok(meta) = attr.parse_meta() {
                 meta
@@ -102,10 +162,85 @@ impl ArgAttrs {
                     ));
                 };
                 glob = Some(lit.value());
+            } else if meta.path().is_ident("compress") {
+                if compress.is_some() {
+                    return Err(syn::Error::new(
+                        attr.span(),
+                        "Duplicate #[compress] attribute",
+                    ));
+                }
+                let meta = if let Meta::NameValue(meta) = &meta {
+                    meta
+                } else {
+                    return Err(syn::Error::new(meta.span(), "Expected #[compress = ...]"));
+                };
+                let lit = if let Lit::Str(lit) = &meta.lit {
+                    lit
+                } else {
+                    return Err(syn::Error::new(
+                        meta.lit.span(),
+                        "Expected a string literal",
+                    ));
+                };
+                compress = Some(
+                    lit.value()
+                        .parse()
+                        .map_err(|e| syn::Error::new(lit.span(), e))?,
+                );
+            } else if meta.path().is_ident("input") {
+                if input {
+                    return Err(syn::Error::new(attr.span(), "Duplicate #[input] attribute"));
+                }
+                if !matches!(meta, Meta::Path(_)) {
+                    return Err(syn::Error::new(meta.span(), "Expected #[input]"));
+                }
+                input = true;
+            } else if meta.path().is_ident("block") {
+                if block.is_some() {
+                    return Err(syn::Error::new(attr.span(), "Duplicate #[block] attribute"));
+                }
+                let meta = if let Meta::NameValue(meta) = &meta {
+                    meta
+                } else {
+                    return Err(syn::Error::new(meta.span(), "Expected #[block = ...]"));
+                };
+                let lit = if let Lit::Int(lit) = &meta.lit {
+                    lit
+                } else {
+                    return Err(syn::Error::new(
+                        meta.lit.span(),
+                        "Expected an integer literal",
+                    ));
+                };
+                block = Some(lit.base10_parse()?);
+            } else if meta.path().is_ident("part") {
+                if part.is_some() {
+                    return Err(syn::Error::new(attr.span(), "Duplicate #[part] attribute"));
+                }
+                let meta = if let Meta::NameValue(meta) = &meta {
+                    meta
+                } else {
+                    return Err(syn::Error::new(meta.span(), "Expected #[part = ...]"));
+                };
+                let lit = if let Lit::Int(lit) = &meta.lit {
+                    lit
+                } else {
+                    return Err(syn::Error::new(
+                        meta.lit.span(),
+                        "Expected an integer literal",
+                    ));
+                };
+                part = Some(lit.base10_parse()?);
             }
         }
         let glob = glob.ok_or_else(|| syn::Error::new(span, "Missing argument: #[glob = ...]"))?;
-        Ok(Self { glob })
+        Ok(Self {
+            glob,
+            compress,
+            input,
+            block,
+            part,
+        })
     }
 }
 
@@ -140,6 +275,13 @@ mod tests {
         assert_eq!(args.root, None);
     }
 
+    #[test]
+    fn test_parse_macro_args_lock() {
+        let args = quote! {lock = "tests/testdata.lock"};
+        let args = MacroArgs::parse(args).unwrap();
+        assert_eq!(args.lock, Some("tests/testdata.lock".to_owned()));
+    }
+
     #[test]
     fn test_parse_macro_args_unknown_arg() {
         let args = quote! {foo = 42};
@@ -201,6 +343,110 @@ mod tests {
         assert_eq!(attrs.glob, "tests/fixtures/**/*-in.txt");
     }
 
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_parse_records_args() {
+        let args = RecordsArgs::parse(quote! { file = "tests/cases.csv", key = "name" }).unwrap();
+        assert_eq!(args.file, "tests/cases.csv");
+        assert_eq!(args.key.as_deref(), Some("name"));
+        assert_eq!(args.rebuild, None);
+
+        let e = RecordsArgs::parse(quote! { key = "name" }).unwrap_err();
+        assert_eq!(e.to_string(), "Missing argument: file = \"...\"");
+
+        let e = RecordsArgs::parse(quote! { file = "a.csv", glob = "*.csv" }).unwrap_err();
+        assert_eq!(e.to_string(), "unknown argument: glob");
+
+        let e = RecordsArgs::parse(quote! { file = "a.csv", file = "b.csv" }).unwrap_err();
+        assert_eq!(e.to_string(), "duplicate argument");
+    }
+
+    #[test]
+    fn test_parse_arg_attrs_compress() {
+        let item = parse_quote! {
+            #[glob = "tests/fixtures/**/*-out.txt"]
+            #[compress = "zstd"]
+            x: PathBuf
+        };
+        let attrs = ArgAttrs::parse(&item).unwrap();
+        assert_eq!(attrs.compress, Some(Compression::Zstd));
+
+        let item = parse_quote! {
+            #[glob = "tests/fixtures/**/*-out.txt"]
+            #[compress = "xz"]
+            x: PathBuf
+        };
+        let e = ArgAttrs::parse(&item).unwrap_err();
+        assert_eq!(e.to_string(), "Unknown compression: \"xz\"");
+    }
+
+    #[test]
+    fn test_parse_arg_attrs_input() {
+        let item = parse_quote! {
+            #[glob = "tests/fixtures/**/*-in.txt"]
+            #[input]
+            x: PathBuf
+        };
+        let attrs = ArgAttrs::parse(&item).unwrap();
+        assert!(attrs.input);
+
+        let item = parse_quote! {
+            #[glob = "tests/fixtures/**/*-in.txt"]
+            #[input = true]
+            x: PathBuf
+        };
+        let e = ArgAttrs::parse(&item).unwrap_err();
+        assert_eq!(e.to_string(), "Expected #[input]");
+
+        let item = parse_quote! {
+            #[glob = "tests/fixtures/**/*-in.txt"]
+            #[input]
+            #[input]
+            x: PathBuf
+        };
+        let e = ArgAttrs::parse(&item).unwrap_err();
+        assert_eq!(e.to_string(), "Duplicate #[input] attribute");
+    }
+
+    #[test]
+    fn test_parse_arg_attrs_block() {
+        let item = parse_quote! {
+            #[glob = "tests/fixtures/*.md"]
+            #[block = 1]
+            x: String
+        };
+        let attrs = ArgAttrs::parse(&item).unwrap();
+        assert_eq!(attrs.block, Some(1));
+
+        let item = parse_quote! {
+            #[glob = "tests/fixtures/*.md"]
+            #[block = "1"]
+            x: String
+        };
+        let e = ArgAttrs::parse(&item).unwrap_err();
+        assert_eq!(e.to_string(), "Expected an integer literal");
+    }
+
+    #[test]
+    fn test_parse_arg_attrs_part() {
+        let item = parse_quote! {
+            #[glob = "tests/corpus/*.txt"]
+            #[part = 0]
+            x: String
+        };
+        let attrs = ArgAttrs::parse(&item).unwrap();
+        assert_eq!(attrs.part, Some(0));
+
+        let item = parse_quote! {
+            #[glob = "tests/corpus/*.txt"]
+            #[part = 0]
+            #[part = 1]
+            x: String
+        };
+        let e = ArgAttrs::parse(&item).unwrap_err();
+        assert_eq!(e.to_string(), "Duplicate #[part] attribute");
+    }
+
     #[test]
     fn test_parse_arg_attrs_self() {
         let item = parse_quote! {
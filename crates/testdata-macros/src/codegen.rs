@@ -19,12 +19,14 @@ pub(crate) fn generate(
     } else {
         "."
     };
-    let spec_def = generate_glob_spec(spec, rt_root);
+    let spec_def = generate_glob_spec(spec, rt_root, macro_args.lock.as_deref());
 
     let function_name = &item.sig.ident;
-    let tree = StemTree::build(&stems);
+    let tree = StemTree::build(stems, spec.has_blocks() || spec.has_parts());
 
-    let tree_tokens = generate_tree(&tree, 0, &item.sig.inputs, function_name);
+    let tree_tokens = generate_tree(&tree, 0, &|name, def, depth| {
+        generate_fn(name, def, depth, &item.sig.inputs, function_name)
+    });
 
     let base_function = {
         let mut base_function = item.clone();
@@ -41,10 +43,16 @@ pub(crate) fn generate(
                 FnArg::Receiver(arg) => &mut arg.attrs,
                 FnArg::Typed(arg) => &mut arg.attrs,
             };
-            // Remove #[glob = "..."] from the parameter attributes
+            // Remove #[glob = "..."], #[compress = "..."], #[input], #[block = ...]
+            // and #[part = ...] from the parameter attributes
             attrs.retain(|attr| {
                 if let Ok(meta) = attr.parse_meta() {
-                    !meta.path().is_ident("glob")
+                    let path = meta.path();
+                    !path.is_ident("glob")
+                        && !path.is_ident("compress")
+                        && !path.is_ident("input")
+                        && !path.is_ident("block")
+                        && !path.is_ident("part")
                 } else {
                     true
                 }
@@ -69,41 +77,60 @@ pub(crate) fn generate(
     }
 }
 
-fn generate_glob_spec(spec: &GlobSpec, rt_root: &str) -> TokenStream {
+fn generate_glob_spec(spec: &GlobSpec, rt_root: &str, lock: Option<&str>) -> TokenStream {
     let rt = get_rt();
     let args = spec
         .args
         .iter()
         .map(|arg| {
             let path = arg.glob.to_string();
+            let name = arg.name.as_ref().map(|name| quote! { .name(#name) });
+            let compression = arg.compression.map(|compression| {
+                let compression = compression.to_string();
+                quote! { .compression(#rt::Compression::new(#compression)) }
+            });
+            let input = if arg.input {
+                Some(quote! { .input() })
+            } else {
+                None
+            };
+            let block = arg.block.map(|block| quote! { .block(#block) });
+            let part = arg.part.map(|part| quote! { .part(#part) });
             quote! {
-                .arg(#rt::ArgSpec::new(#path))
+                .arg(#rt::ArgSpec::new(#path)#name #compression #input #block #part)
             }
         })
         .collect::<Vec<_>>();
+    let lock = lock.map(|lock| {
+        quote! {
+            .name(module_path!())
+            .lock_file(std::path::Path::new(#lock))
+        }
+    });
     quote! {
         const __GLOB_SPEC: #rt::Lazy<#rt::GlobSpec> = #rt::Lazy::new(|| {
             #rt::GlobSpec::new()
                 .root(std::path::Path::new(#rt_root))
+                #lock
                 #(#args)*
         });
     }
 }
 
-fn generate_tree(
+/// Generates the nested modules, with test functions generated by `generate_fn`.
+pub(crate) fn generate_tree(
     tree: &StemTree,
     depth: usize,
-    args: &Punctuated<FnArg, Token![,]>,
-    base_function_name: &Ident,
+    generate_fn: &dyn Fn(&str, &StemFn, usize) -> TokenStream,
 ) -> TokenStream {
     let fns = sorted_iter(&tree.fns)
-        .map(|(name, def)| generate_fn(name, def, depth, args, base_function_name))
+        .map(|(name, def)| generate_fn(name, def, depth))
         .collect::<Vec<_>>();
 
     let mods = sorted_iter(&tree.mods)
         .map(|(name, def)| {
             let name = Ident::new(name, Span::call_site());
-            let sub = generate_tree(def, depth + 1, args, base_function_name);
+            let sub = generate_tree(def, depth + 1, generate_fn);
             quote! {
                 mod #name {
                     #sub
@@ -130,14 +157,7 @@ fn generate_fn(
     let super_ref = up(depth + 1);
     let name = Ident::new(name, Span::call_site());
     let stem = &def.stem;
-    let arg_forwards = (0..args.len())
-        .map(|i| {
-            let i = Literal::usize_unsuffixed(i);
-            quote! {
-                &paths[#i]
-            }
-        })
-        .collect::<Vec<_>>();
+    let arg_forwards = generate_arg_forwards(args);
     quote! {
         #[test]
         fn #name() {
@@ -162,14 +182,7 @@ fn generate_fallback_fn(
     let stems_literal = quote! {
         vec![#(#stems_literal),*]
     };
-    let arg_forwards = (0..args.len())
-        .map(|i| {
-            let i = Literal::usize_unsuffixed(i);
-            quote! {
-                &paths[#i]
-            }
-        })
-        .collect::<Vec<_>>();
+    let arg_forwards = generate_arg_forwards(args);
     let rebuilder = if let Some(rebuild_path) = &macro_args.rebuild {
         quote! {
             if diff.has_diff {
@@ -200,6 +213,19 @@ fn generate_fallback_fn(
     }
 }
 
+/// Converts the test files to the parameter types, reading them if necessary.
+fn generate_arg_forwards(args: &Punctuated<FnArg, Token![,]>) -> Vec<TokenStream> {
+    let rt = get_rt();
+    (0..args.len())
+        .map(|i| {
+            let i = Literal::usize_unsuffixed(i);
+            quote! {
+                #rt::FromTestFile::from_test_file(&paths[#i])
+            }
+        })
+        .collect()
+}
+
 fn sorted_iter<'a, K, V, S>(h: &'a HashMap<K, V, S>) -> impl Iterator<Item = (&'a K, &'a V)> + 'a
 where
     K: Ord + std::hash::Hash,
@@ -210,7 +236,7 @@ where
     keys.into_iter().map(move |k| (k, &h[k]))
 }
 
-fn up(depth: usize) -> TokenStream {
+pub(crate) fn up(depth: usize) -> TokenStream {
     if depth == 0 {
         quote! { self }
     } else {
@@ -226,7 +252,7 @@ fn up(depth: usize) -> TokenStream {
     }
 }
 
-fn get_rt() -> TokenStream {
+pub(crate) fn get_rt() -> TokenStream {
     quote! {
         testdata::__rt
     }
@@ -236,7 +262,7 @@ fn get_rt() -> TokenStream {
 mod tests {
     use big_s::S;
     use syn::parse_quote;
-    use testdata_rt::ArgSpec;
+    use testdata_rt::{ArgSpec, Compression};
 
     use crate::assert_ts_eq;
 
@@ -261,6 +287,7 @@ mod tests {
         let macro_args = MacroArgs {
             rebuild: None,
             root: None,
+            lock: None,
         };
         let tokens = generate(
             &spec,
@@ -294,13 +321,13 @@ mod tests {
                     #[test]
                     fn bar() {
                         if let Some(paths) = testdata::__rt::GlobSpecExt::expand(&*self::__GLOB_SPEC, "bar") {
-                            super::test_foo(&paths[0], &paths[1]);
+                            super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
                         }
                     }
                     #[test]
                     fn foo() {
                         if let Some(paths) = testdata::__rt::GlobSpecExt::expand(&*self::__GLOB_SPEC, "foo") {
-                            super::test_foo(&paths[0], &paths[1]);
+                            super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
                         }
                     }
                     mod foo {
@@ -309,7 +336,7 @@ mod tests {
                             if let Some(paths) =
                                 testdata::__rt::GlobSpecExt::expand(&*super::__GLOB_SPEC, "foo/bar-baz")
                             {
-                                super::super::test_foo(&paths[0], &paths[1]);
+                                super::super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
                             }
                         }
                         #[test]
@@ -317,7 +344,7 @@ mod tests {
                             if let Some(paths) =
                                 testdata::__rt::GlobSpecExt::expand(&*super::__GLOB_SPEC, "foo/bar_baz")
                             {
-                                super::super::test_foo(&paths[0], &paths[1]);
+                                super::super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
                             }
                         }
                         mod bar {
@@ -327,7 +354,7 @@ mod tests {
                                     &*super::super::__GLOB_SPEC,
                                     "foo/bar/01_todo"
                                 ) {
-                                    super::super::super::test_foo(&paths[0], &paths[1]);
+                                    super::super::super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
                                 }
                             }
                             #[test]
@@ -335,7 +362,7 @@ mod tests {
                                 if let Some(paths) =
                                     testdata::__rt::GlobSpecExt::expand(&*super::super::__GLOB_SPEC, "foo/bar/baz")
                                 {
-                                    super::super::super::test_foo(&paths[0], &paths[1]);
+                                    super::super::super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
                                 }
                             }
                         }
@@ -357,7 +384,7 @@ mod tests {
                                 continue;
                             }
                             let paths = testdata::__rt::GlobSpecExt::expand(&*self::__GLOB_SPEC, stem).unwrap();
-                            super::test_foo(&paths[0], &paths[1]);
+                            super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
                         }
                     }
                 }
@@ -384,6 +411,7 @@ mod tests {
         let macro_args = MacroArgs {
             rebuild: None,
             root: Some("other_root".to_owned()),
+            lock: None,
         };
         let tokens = generate(&spec, &macro_args, &item, &[S("foo")]);
         assert_ts_eq!(
@@ -405,7 +433,81 @@ mod tests {
                     #[test]
                     fn foo() {
                         if let Some(paths) = testdata::__rt::GlobSpecExt::expand(&*self::__GLOB_SPEC, "foo") {
-                            super::test_foo(&paths[0], &paths[1]);
+                            super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
+                        }
+                    }
+                    #[test]
+                    fn __others() {
+                        let known_stems = vec!["foo".to_owned()];
+                        let stems = self::__GLOB_SPEC.glob().unwrap();
+                        let diff = testdata::__rt::diff(&stems, &known_stems);
+                        for stem in &diff.extra {
+                            if known_stems.contains(stem) {
+                                continue;
+                            }
+                            let paths = testdata::__rt::GlobSpecExt::expand(&*self::__GLOB_SPEC, stem).unwrap();
+                            super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
+                        }
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_generate_with_arg_options() {
+        let item = parse_quote! {
+            #[test]
+            fn test_foo(
+                #[glob = "tests/fixtures/**/*-in.txt"]
+                #[compress = "zstd"]
+                #[input]
+                #[block = 0]
+                input: PathBuf,
+            ) {
+                foo();
+            }
+        };
+        let spec = GlobSpec::new().arg(
+            ArgSpec::new("tests/fixtures/**/*-in.txt")
+                .name("input")
+                .compression(Compression::Zstd)
+                .input()
+                .block(0),
+        );
+        let macro_args = MacroArgs {
+            rebuild: None,
+            root: None,
+            lock: Some("tests/testdata.lock".to_owned()),
+        };
+        let tokens = generate(&spec, &macro_args, &item, &[S("foo")]);
+        assert_ts_eq!(
+            tokens,
+            quote! {
+                #[cfg(test)]
+                fn test_foo(input: PathBuf,) {
+                    foo();
+                }
+                #[cfg(test)]
+                mod test_foo {
+                    const __GLOB_SPEC: testdata::__rt::Lazy<testdata::__rt::GlobSpec> =
+                        testdata::__rt::Lazy::new(|| {
+                            testdata::__rt::GlobSpec::new()
+                                .root(std::path::Path::new("."))
+                                .name(module_path!())
+                                .lock_file(std::path::Path::new("tests/testdata.lock"))
+                                .arg(
+                                    testdata::__rt::ArgSpec::new("tests/fixtures/**/*-in.txt")
+                                        .name("input")
+                                        .compression(testdata::__rt::Compression::new("zstd"))
+                                        .input()
+                                        .block(0usize)
+                                )
+                        });
+                    #[test]
+                    fn foo() {
+                        if let Some(paths) = testdata::__rt::GlobSpecExt::expand(&*self::__GLOB_SPEC, "foo") {
+                            super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]));
                         }
                     }
                     #[test]
@@ -418,7 +520,7 @@ mod tests {
                                 continue;
                             }
                             let paths = testdata::__rt::GlobSpecExt::expand(&*self::__GLOB_SPEC, stem).unwrap();
-                            super::test_foo(&paths[0], &paths[1]);
+                            super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]));
                         }
                     }
                 }
@@ -445,6 +547,7 @@ mod tests {
         let macro_args = MacroArgs {
             rebuild: Some("test/integration.rs".to_owned()),
             root: None,
+            lock: None,
         };
         let tokens = generate(&spec, &macro_args, &item, &[S("foo")]);
         assert_ts_eq!(
@@ -466,7 +569,7 @@ mod tests {
                     #[test]
                     fn foo() {
                         if let Some(paths) = testdata::__rt::GlobSpecExt::expand(&*self::__GLOB_SPEC, "foo") {
-                            super::test_foo(&paths[0], &paths[1]);
+                            super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
                         }
                     }
                     #[test]
@@ -479,7 +582,7 @@ mod tests {
                                 continue;
                             }
                             let paths = testdata::__rt::GlobSpecExt::expand(&*self::__GLOB_SPEC, stem).unwrap();
-                            super::test_foo(&paths[0], &paths[1]);
+                            super::test_foo(testdata::__rt::FromTestFile::from_test_file(&paths[0]), testdata::__rt::FromTestFile::from_test_file(&paths[1]));
                         }
                         if diff.has_diff {
                             testdata::__rt::touch(std::path::Path::new("test/integration.rs")).unwrap();
@@ -0,0 +1,159 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, GenericArgument, Lit, Meta, PathArguments, Type};
+
+use crate::codegen::get_rt;
+
+/// Generates the `TestInput` impl reading each field from a section.
+pub(crate) fn derive_test_input(input: &DeriveInput) -> Result<TokenStream, syn::Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "#[derive(TestInput)] requires named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "#[derive(TestInput)] only supports structs",
+            ))
+        }
+    };
+
+    let rt = get_rt();
+    let mut names = Vec::new();
+    let mut inits = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let name = section_name(field)?.unwrap_or_else(|| ident.to_string());
+        let init = if option_inner(&field.ty).is_some() {
+            quote! { __sections.field_opt(#name)? }
+        } else {
+            quote! { __sections.field(#name)? }
+        };
+        inits.push(quote! { #ident: #init });
+        names.push(name);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #rt::TestInput for #ident #ty_generics #where_clause {
+            type Err = #rt::SectionError;
+            fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+                let __sections = #rt::Sections::parse(data, &[#(#names),*])?;
+                Ok(Self {
+                    #(#inits,)*
+                })
+            }
+            fn error_position(_data: &[u8], err: &Self::Err) -> Option<#rt::Position> {
+                err.position()
+            }
+        }
+    })
+}
+
+/// Reads `#[section = "..."]`.
+fn section_name(field: &syn::Field) -> Result<Option<String>, syn::Error> {
+    let mut name = None;
+    for attr in &field.attrs {
+        if !attr.path.is_ident("section") {
+            continue;
+        }
+        if name.is_some() {
+            return Err(syn::Error::new(
+                attr.span(),
+                "Duplicate #[section] attribute",
+            ));
+        }
+        let meta = attr.parse_meta()?;
+        if let Meta::NameValue(meta) = &meta {
+            if let Lit::Str(lit) = &meta.lit {
+                name = Some(lit.value());
+                continue;
+            }
+        }
+        return Err(syn::Error::new(
+            meta.span(),
+            "Expected #[section = \"...\"]",
+        ));
+    }
+    Ok(name)
+}
+
+/// Detects `Option<T>`, which makes the section optional.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = if let Type::Path(ty) = ty {
+        &ty.path
+    } else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        if let Some(GenericArgument::Type(ty)) = args.args.first() {
+            return Some(ty);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use crate::assert_ts_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_derive_test_input() {
+        let input = parse_quote! {
+            struct Case {
+                input: String,
+                #[section = "expected-output"]
+                output: Vec<u8>,
+                options: Option<String>,
+            }
+        };
+        let tokens = derive_test_input(&input).unwrap();
+        assert_ts_eq!(
+            &tokens,
+            quote! {
+                impl testdata::__rt::TestInput for Case {
+                    type Err = testdata::__rt::SectionError;
+                    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+                        let __sections = testdata::__rt::Sections::parse(
+                            data,
+                            &["input", "expected-output", "options"]
+                        )?;
+                        Ok(Self {
+                            input: __sections.field("input")?,
+                            output: __sections.field("expected-output")?,
+                            options: __sections.field_opt("options")?,
+                        })
+                    }
+                    fn error_position(_data: &[u8], err: &Self::Err) -> Option<testdata::__rt::Position> {
+                        err.position()
+                    }
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_derive_test_input_tuple_struct() {
+        let input = parse_quote! {
+            struct Case(String);
+        };
+        let e = derive_test_input(&input).unwrap_err();
+        assert_eq!(e.to_string(), "#[derive(TestInput)] requires named fields");
+    }
+}
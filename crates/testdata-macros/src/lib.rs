@@ -1,5 +1,8 @@
 mod attrs;
 mod codegen;
+mod derive;
+#[cfg(feature = "json")]
+mod records;
 mod sanitization;
 #[cfg(test)]
 mod testing;
@@ -10,11 +13,18 @@ use std::path::PathBuf;
 
 use proc_macro2::TokenStream;
 use syn::spanned::Spanned;
-use syn::{parse2, Item};
+use syn::{parse2, DeriveInput, FnArg, Item, Pat};
 use testdata_rt::GlobSpec;
+#[cfg(feature = "json")]
+use testdata_rt::RecordSpec;
 
+#[cfg(feature = "json")]
+use crate::attrs::RecordsArgs;
 use crate::attrs::{ArgAttrs, MacroArgs};
 use crate::codegen::generate;
+use crate::derive::derive_test_input;
+#[cfg(feature = "json")]
+use crate::records::{field_names, generate_records};
 
 /// Generates multiple test functions based on files.
 ///
@@ -45,6 +55,19 @@ use crate::codegen::generate;
 /// #[testdata::files(root = "tests/fixtures")]
 /// ```
 ///
+/// ### lock
+///
+/// Path to the lock file, relative to the crate root.
+///
+/// If specified, the hashes of the files for the arguments marked as `#[input]`
+/// are recorded in the lock file, and reading a modified input fails
+/// with the list of changed inputs.
+/// Run the tests with `UPDATE_FIXTURE_LOCK=1` to update the lock file.
+///
+/// ```rust,ignore
+/// #[testdata::files(lock = "tests/testdata.lock")]
+/// ```
+///
 /// ## Attributes on function arguments
 ///
 /// ### glob
@@ -79,6 +102,107 @@ use crate::codegen::generate;
 ///
 /// - For reading, the first one existing will be picked.
 /// - For writing, the first one, whether existing or not, will be picked.
+/// - An empty file prefixed with `.wh.` (a whiteout marker, like `.wh.foo-out.txt`)
+///   hides the file in the later candidates.
+///   Removing a file that still exists in a later candidate creates one.
+///
+/// `TestFile::copy_up` copies the file into the first candidate, and
+/// `TestFile::source_layer` tells which candidate the file is read from.
+///
+/// ### compress
+///
+/// Stores the file compressed when written, for example as a snapshot.
+/// Either `gzip` or `zstd`.
+///
+/// Compressed files (`foo-out.txt.gz` or `foo-out.txt.zst`) are always
+/// found and read as if they were uncompressed (`foo-out.txt`).
+///
+/// ```rust,ignore
+/// fn f(
+///     #[glob = "tests/fixtures/**/*-out.txt"]
+///     #[compress = "zstd"]
+///     output: &TestFile,
+/// ) {}
+/// ```
+///
+/// ### input
+///
+/// Marks the argument as an input, recorded in the lock file (see `lock` above).
+///
+/// ```rust,ignore
+/// fn f(
+///     #[glob = "tests/fixtures/**/*-in.txt"]
+///     #[input]
+///     input: &TestFile,
+/// ) {}
+/// ```
+///
+/// ### block
+///
+/// Reads the argument from a fenced code block in a Markdown document,
+/// counted from zero in each section.
+/// Each section (a heading followed by code blocks) becomes a test case,
+/// named like `spec#hello-world` after the file and the heading.
+///
+/// Writing to the file replaces the code block in place.
+/// Writing to the block right after the last one adds a new code block to the section,
+/// so that a missing expected output can be filled in.
+///
+/// ```rust,ignore
+/// fn f(
+///     #[glob = "tests/fixtures/*.md"]
+///     #[block = 0]
+///     input: String,
+///     #[glob = "tests/fixtures/*.md"]
+///     #[block = 1]
+///     output: &TestFile,
+/// ) {}
+/// ```
+///
+/// ### part
+///
+/// Reads the argument from a part of a test case in a file containing multiple test cases,
+/// counted from zero in each case.
+/// Each case starts with a header line like `=== name`, or the name between two lines of `=`
+/// as in tree-sitter corpus files, and its parts are separated by `---` lines.
+/// The test cases are named like `corpus#hello-world` after the file and the header.
+///
+/// Writing to the file replaces the part in place,
+/// or adds a new part after the last one as with `block`.
+///
+/// ```text
+/// === Hello, world!
+/// hello, world
+/// ---
+/// HELLO, WORLD
+/// ```
+///
+/// ```rust,ignore
+/// fn f(
+///     #[glob = "tests/corpus/*.txt"]
+///     #[part = 0]
+///     input: String,
+///     #[glob = "tests/corpus/*.txt"]
+///     #[part = 1]
+///     output: &TestFile,
+/// ) {}
+/// ```
+///
+/// ## Parameter types
+///
+/// A parameter is either `&TestFile` or any type implementing `TestInput`,
+/// like `String` or `Json<T>`.
+/// The latter is read and parsed before the test function is called,
+/// and the test fails with the path to the file if it cannot be parsed.
+///
+/// ```rust,ignore
+/// fn f(
+///     #[glob = "tests/fixtures/**/*-in.txt"]
+///     input: String,
+///     #[glob = "tests/fixtures/**/*-out.txt"]
+///     output: &TestFile,
+/// ) {}
+/// ```
 ///
 /// ## Example
 ///
@@ -98,6 +222,7 @@ use crate::codegen::generate;
 ///     assert_snapshot!(result, snapshot = output);
 /// }
 /// ```
+#[allow(clippy::test_attr_in_doctest)]
 #[proc_macro_attribute]
 pub fn files(
     raw_args: proc_macro::TokenStream,
@@ -133,9 +258,27 @@ fn files2(raw_args: TokenStream, raw_item: TokenStream) -> Result<TokenStream, s
     if let Some(root) = &args.root {
         spec.root = root.into();
     }
-    for attrs in &args_attrs {
-        spec.args
-            .push(testdata_rt::ArgSpec::parse(&attrs.glob).map_err(|e| syn::Error::new(span, e))?);
+    for (arg, attrs) in item.sig.inputs.iter().zip(&args_attrs) {
+        let mut arg_spec =
+            testdata_rt::ArgSpec::parse(&attrs.glob).map_err(|e| syn::Error::new(span, e))?;
+        if let FnArg::Typed(arg) = arg {
+            if let Pat::Ident(pat) = &*arg.pat {
+                arg_spec = arg_spec.name(&pat.ident.to_string());
+            }
+        }
+        if let Some(compress) = attrs.compress {
+            arg_spec = arg_spec.compression(compress);
+        }
+        if attrs.input {
+            arg_spec = arg_spec.input();
+        }
+        if let Some(block) = attrs.block {
+            arg_spec = arg_spec.block(block);
+        }
+        if let Some(part) = attrs.part {
+            arg_spec = arg_spec.part(part);
+        }
+        spec.args.push(arg_spec);
     }
 
     let cwd = env::var_os("CARGO_MANIFEST_DIR")
@@ -145,3 +288,123 @@ fn files2(raw_args: TokenStream, raw_item: TokenStream) -> Result<TokenStream, s
 
     Ok(generate(&spec, &args, &item, &stems))
 }
+
+/// Generates a test function for each record in a JSON Lines or CSV file.
+///
+/// Each parameter is read from the field of the same name, through serde.
+/// CSV fields are parsed according to the parameter type,
+/// and empty or missing fields are read as `None` for `Option<T>`.
+/// Requires the `json` feature, even for CSV files.
+///
+/// ## Macro arguments
+///
+/// - `file`: path to the file, either `.jsonl`, `.ndjson` or `.csv` (with a header row).
+/// - `key`: the field to name the tests after. If absent, the tests are named
+///   after the line numbers, like `line_3`.
+/// - `rebuild`: same as in [`macro@files`].
+///
+/// Records added after the build are run in `__others`, as with [`macro@files`].
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// // tests/cases.csv:
+/// // name,a,b,sum
+/// // zeros,0,0,0
+/// // one-plus-one,1,1,2
+/// #[testdata::records(file = "tests/cases.csv", key = "name")]
+/// #[test]
+/// fn test_add(a: i32, b: i32, sum: i32) {
+///     assert_eq!(a + b, sum);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn records(
+    raw_args: proc_macro::TokenStream,
+    raw_item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    match records2(raw_args.into(), raw_item.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[cfg(not(feature = "json"))]
+fn records2(raw_args: TokenStream, _raw_item: TokenStream) -> Result<TokenStream, syn::Error> {
+    Err(syn::Error::new(
+        raw_args.span(),
+        "#[testdata::records] requires the `json` feature of testdata",
+    ))
+}
+
+#[cfg(feature = "json")]
+fn records2(raw_args: TokenStream, raw_item: TokenStream) -> Result<TokenStream, syn::Error> {
+    let span = raw_args.span();
+    let args = RecordsArgs::parse(raw_args)?;
+
+    let item: Item = parse2(raw_item)?;
+    let item = if let Item::Fn(item) = item {
+        item
+    } else {
+        return Err(syn::Error::new(
+            item.span(),
+            "expected function after #[testdata::records]",
+        ));
+    };
+    let fields = field_names(&item)?;
+
+    let mut spec = RecordSpec::new(args.file.as_ref());
+    if let Some(key) = &args.key {
+        spec = spec.key(key);
+    }
+    let cwd = env::var_os("CARGO_MANIFEST_DIR")
+        .ok_or_else(|| syn::Error::new(span, "Missing CARGO_MANIFEST_DIR"))?;
+    let cwd = PathBuf::from(cwd);
+    let names = spec
+        .read_from(&cwd)
+        .map_err(|e| syn::Error::new(span, e))?
+        .into_iter()
+        .map(|record| record.name)
+        .collect::<Vec<_>>();
+
+    Ok(generate_records(&args, &item, &fields, &names))
+}
+
+/// Implements `TestInput` for a struct by reading each field from a section of the file.
+///
+/// Sections start with a header line like `--- input ---`.
+/// Each field is read from the section of the same name through its own `TestInput` impl,
+/// and `#[section = "..."]` renames the section.
+/// Fields of type `Option<T>` are read from optional sections.
+///
+/// Errors report the name of the section and the line number of its header.
+///
+/// ## Example
+///
+/// ```rust
+/// use testdata::TestInput;
+///
+/// #[derive(TestInput)]
+/// struct Case {
+///     input: String,
+///     #[section = "expected-output"]
+///     output: String,
+///     options: Option<String>,
+/// }
+///
+/// let case = Case::read_from(b"--- input ---\nfoo\n--- expected-output ---\nFOO\n");
+/// assert_eq!(case.input, "foo\n");
+/// assert_eq!(case.output, "FOO\n");
+/// assert_eq!(case.options, None);
+/// ```
+#[proc_macro_derive(TestInput, attributes(section))]
+pub fn derive_test_input_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = match parse2::<DeriveInput>(input.into()) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    match derive_test_input(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
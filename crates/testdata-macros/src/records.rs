@@ -0,0 +1,214 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{FnArg, ItemFn, Pat};
+
+use crate::attrs::RecordsArgs;
+use crate::codegen::{generate_tree, get_rt, up};
+use crate::tree::{StemFn, StemTree};
+
+/// Takes the field names from the parameter names.
+pub(crate) fn field_names(item: &ItemFn) -> Result<Vec<String>, syn::Error> {
+    item.sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Typed(arg) => match &*arg.pat {
+                Pat::Ident(pat) => Ok(pat.ident.unraw().to_string()),
+                pat => Err(syn::Error::new(pat.span(), "Expected a parameter name")),
+            },
+            FnArg::Receiver(arg) => Err(syn::Error::new(arg.span(), "Unexpected self")),
+        })
+        .collect()
+}
+
+pub(crate) fn generate_records(
+    args: &RecordsArgs,
+    item: &ItemFn,
+    fields: &[String],
+    names: &[String],
+) -> TokenStream {
+    let rt = get_rt();
+    let function_name = &item.sig.ident;
+    let file = &args.file;
+    let key = args.key.as_ref().map(|key| quote! { .key(#key) });
+    let spec_def = quote! {
+        const __RECORDS: #rt::Lazy<#rt::RecordSpec> = #rt::Lazy::new(|| {
+            #rt::RecordSpec::new(std::path::Path::new(#file)) #key
+        });
+    };
+
+    let tree = StemTree::build(names, false);
+    let tree_tokens = generate_tree(&tree, 0, &|name, def, depth| {
+        generate_record_fn(name, def, depth, fields, function_name)
+    });
+    let fallback_fn = generate_fallback_fn(names, args, fields, function_name);
+
+    let base_function = {
+        let mut base_function = item.clone();
+        // Remove #[test] from the function attributes
+        base_function.attrs.retain(|attr| {
+            if let Ok(meta) = attr.parse_meta() {
+                !meta.path().is_ident("test")
+            } else {
+                true
+            }
+        });
+        base_function
+    };
+
+    quote! {
+        #[cfg(test)]
+        #base_function
+
+        #[cfg(test)]
+        mod #function_name {
+            #spec_def
+
+            #tree_tokens
+
+            #fallback_fn
+        }
+    }
+}
+
+fn generate_record_fn(
+    name: &str,
+    def: &StemFn,
+    depth: usize,
+    fields: &[String],
+    base_function_name: &Ident,
+) -> TokenStream {
+    let self_ref = up(depth);
+    let super_ref = up(depth + 1);
+    let name = Ident::new(name, Span::call_site());
+    let record_name = &def.stem;
+    let field_forwards = generate_field_forwards(fields, &self_ref);
+    quote! {
+        #[test]
+        fn #name() {
+            if let Some(record) = #self_ref::__RECORDS.find(#record_name).unwrap() {
+                #super_ref::#base_function_name(#(#field_forwards),*);
+            }
+        }
+    }
+}
+
+fn generate_fallback_fn(
+    names: &[String],
+    args: &RecordsArgs,
+    fields: &[String],
+    base_function_name: &Ident,
+) -> TokenStream {
+    let rt = get_rt();
+    let field_forwards = generate_field_forwards(fields, &quote! { self });
+    let rebuilder = args.rebuild.as_ref().map(|rebuild_path| {
+        quote! {
+            if diff.has_diff {
+                #rt::touch(std::path::Path::new(#rebuild_path)).unwrap();
+            }
+        }
+    });
+    quote! {
+        #[test]
+        fn __others() {
+            let known_names = vec![#(#names.to_owned()),*];
+            let records = self::__RECORDS.read().unwrap();
+            let names = records
+                .iter()
+                .map(|record| record.name.clone())
+                .collect::<Vec<_>>();
+            let diff = #rt::diff(&names, &known_names);
+            for record in records.iter().filter(|record| diff.extra.contains(&record.name)) {
+                super::#base_function_name(#(#field_forwards),*);
+            }
+            #rebuilder
+        }
+    }
+}
+
+/// Reads the fields of `record` as the parameter types.
+fn generate_field_forwards(fields: &[String], self_ref: &TokenStream) -> Vec<TokenStream> {
+    let rt = get_rt();
+    fields
+        .iter()
+        .map(|field| {
+            quote! {
+                #rt::record_field(&*#self_ref::__RECORDS, &record, #field)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use syn::parse_quote;
+
+    use crate::assert_ts_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_generate_records() {
+        let item = parse_quote! {
+            #[test]
+            fn test_add(a: i32, r#b: i32, sum: i32) {
+                assert_eq!(a + b, sum);
+            }
+        };
+        let args = RecordsArgs {
+            file: S("tests/cases.csv"),
+            key: Some(S("name")),
+            rebuild: None,
+        };
+        let fields = field_names(&item).unwrap();
+        assert_eq!(fields, vec![S("a"), S("b"), S("sum")]);
+        let tokens = generate_records(&args, &item, &fields, &[S("one-plus-one")]);
+        assert_ts_eq!(
+            tokens,
+            quote! {
+                #[cfg(test)]
+                fn test_add(a: i32, r#b: i32, sum: i32) {
+                    assert_eq!(a + b, sum);
+                }
+                #[cfg(test)]
+                mod test_add {
+                    const __RECORDS: testdata::__rt::Lazy<testdata::__rt::RecordSpec> =
+                        testdata::__rt::Lazy::new(|| {
+                            testdata::__rt::RecordSpec::new(std::path::Path::new("tests/cases.csv"))
+                                .key("name")
+                        });
+                    #[test]
+                    fn one_plus_one() {
+                        if let Some(record) = self::__RECORDS.find("one-plus-one").unwrap() {
+                            super::test_add(
+                                testdata::__rt::record_field(&*self::__RECORDS, &record, "a"),
+                                testdata::__rt::record_field(&*self::__RECORDS, &record, "b"),
+                                testdata::__rt::record_field(&*self::__RECORDS, &record, "sum")
+                            );
+                        }
+                    }
+                    #[test]
+                    fn __others() {
+                        let known_names = vec!["one-plus-one".to_owned()];
+                        let records = self::__RECORDS.read().unwrap();
+                        let names = records
+                            .iter()
+                            .map(|record| record.name.clone())
+                            .collect::<Vec<_>>();
+                        let diff = testdata::__rt::diff(&names, &known_names);
+                        for record in records.iter().filter(|record| diff.extra.contains(&record.name)) {
+                            super::test_add(
+                                testdata::__rt::record_field(&*self::__RECORDS, &record, "a"),
+                                testdata::__rt::record_field(&*self::__RECORDS, &record, "b"),
+                                testdata::__rt::record_field(&*self::__RECORDS, &record, "sum")
+                            );
+                        }
+                    }
+                }
+            }
+        );
+    }
+}
@@ -3,8 +3,8 @@ use std::process::{Command, Stdio};
 
 pub(crate) fn rustfmt(s: &str) -> io::Result<String> {
     let mut rustfmt = Command::new("rustfmt")
-        .args(&["--edition", "2021"])
-        .args(&["--color", "never"])
+        .args(["--edition", "2021"])
+        .args(["--color", "never"])
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .spawn()?;
@@ -17,10 +17,10 @@ pub(crate) fn rustfmt(s: &str) -> io::Result<String> {
 
     let rustfmt_output = rustfmt.wait_with_output()?;
     if !rustfmt_output.status.success() {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("rustfmt failed: {:?}", rustfmt_output),
-        ));
+        return Err(io::Error::other(format!(
+            "rustfmt failed: {:?}",
+            rustfmt_output
+        )));
     }
     let src = String::from_utf8_lossy(&rustfmt_output.stdout).into_owned();
     Ok(src)
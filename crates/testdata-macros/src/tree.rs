@@ -12,17 +12,30 @@ pub(crate) struct StemTree {
 }
 
 impl StemTree {
-    pub(crate) fn build(stems: &[String]) -> Self {
+    /// Builds the tree of the stems.
+    ///
+    /// With `sections`, `#` also separates the test cases in a file, like
+    /// the sections of a Markdown document, as in [`GlobSpec::split_stem`].
+    ///
+    /// [`GlobSpec::split_stem`]: testdata_rt::GlobSpec::split_stem
+    pub(crate) fn build(stems: &[String], sections: bool) -> Self {
         let mut tree = Self::default();
         for stem in stems {
-            tree.add(stem);
+            tree.add(stem, sections);
         }
         tree
     }
 
-    pub(crate) fn add(&mut self, stem: &str) {
+    pub(crate) fn add(&mut self, stem: &str, sections: bool) {
         let mut current = self;
-        let parts = stem.split('/').collect::<Vec<_>>();
+        let parts = match stem.split_once('#') {
+            Some((file_stem, slug)) if sections => {
+                let mut parts = file_stem.split('/').collect::<Vec<_>>();
+                parts.push(slug);
+                parts
+            }
+            _ => stem.split('/').collect::<Vec<_>>(),
+        };
         for &part in &parts[..parts.len() - 1] {
             let part = sanitize_component(part);
             current = current.mods.entry(part).or_default();
@@ -60,14 +73,17 @@ mod tests {
 
     #[test]
     fn test_build() {
-        let tree = StemTree::build(&[
-            S("bar"),
-            S("foo"),
-            S("foo/bar-baz"),
-            S("foo/bar/01_todo"),
-            S("foo/bar/baz"),
-            S("foo/bar_baz"),
-        ]);
+        let tree = StemTree::build(
+            &[
+                S("bar"),
+                S("foo"),
+                S("foo/bar-baz"),
+                S("foo/bar/01_todo"),
+                S("foo/bar/baz"),
+                S("foo/bar_baz"),
+            ],
+            false,
+        );
         assert_eq!(
             tree,
             StemTree {
@@ -107,4 +123,47 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_build_markdown() {
+        let tree = StemTree::build(&[S("spec#hello-world"), S("spec#empty")], true);
+        assert_eq!(
+            tree,
+            StemTree {
+                fns: hashmap! {},
+                mods: hashmap! {
+                    S("spec") => StemTree {
+                        fns: hashmap! {
+                            S("hello_world") => StemFn {
+                                stem: S("spec#hello-world"),
+                            },
+                            S("empty") => StemFn {
+                                stem: S("spec#empty"),
+                            },
+                        },
+                        mods: hashmap! {},
+                    },
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_hash_without_sections() {
+        let tree = StemTree::build(&[S("issue#1"), S("issue#2")], false);
+        assert_eq!(
+            tree,
+            StemTree {
+                fns: hashmap! {
+                    S("issue_1") => StemFn {
+                        stem: S("issue#1"),
+                    },
+                    S("issue_2") => StemFn {
+                        stem: S("issue#2"),
+                    },
+                },
+                mods: hashmap! {},
+            }
+        );
+    }
 }
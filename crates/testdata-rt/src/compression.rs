@@ -0,0 +1,84 @@
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// An unknown compression name.
+#[derive(Debug, Error)]
+#[error("Unknown compression: {src:?}")]
+pub struct CompressionParseError {
+    src: String,
+}
+
+/// Compression formats test files can be stored in.
+///
+/// A compressed file has an extra extension, like `foo-out.txt.zst`,
+/// and is otherwise treated the same as the uncompressed one (`foo-out.txt`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Compression {
+    /// gzip, stored with the `.gz` extension.
+    Gzip,
+    /// Zstandard, stored with the `.zst` extension.
+    Zstd,
+}
+
+impl Compression {
+    /// Creates a compression from its name, like `zstd`.
+    pub fn new(src: &str) -> Self {
+        src.parse().unwrap()
+    }
+
+    /// All the known compression formats, in the order of lookup.
+    pub const ALL: &'static [Compression] = &[Compression::Gzip, Compression::Zstd];
+
+    /// Returns the file extension, without the leading dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gz",
+            Compression::Zstd => "zst",
+        }
+    }
+
+    /// Removes the compression extension from the file name, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use testdata_rt::Compression;
+    /// assert_eq!(
+    ///     Compression::strip_extension("foo-out.txt.zst"),
+    ///     Some(("foo-out.txt", Compression::Zstd)),
+    /// );
+    /// assert_eq!(Compression::strip_extension("foo-out.txt"), None);
+    /// ```
+    pub fn strip_extension(file_name: &str) -> Option<(&str, Compression)> {
+        Self::ALL.iter().find_map(|&compression| {
+            let stripped = file_name.strip_suffix(compression.extension())?;
+            let stripped = stripped.strip_suffix('.')?;
+            Some((stripped, compression))
+        })
+    }
+}
+
+impl FromStr for Compression {
+    type Err = CompressionParseError;
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        match src {
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            _ => Err(CompressionParseError {
+                src: src.to_owned(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Compression::Gzip => f.write_str("gzip"),
+            Compression::Zstd => f.write_str("zstd"),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::markdown::unique_slug;
+
+/// A test case in a file delimited by `===` headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelimitedCase {
+    /// The name in the header, like `Hello, world!`.
+    pub name: String,
+    /// The slug made from the name, like `hello-world`. Unique in the file.
+    pub slug: String,
+    /// Byte ranges of the parts separated by `---` lines,
+    /// without the surrounding blank lines.
+    pub parts: Vec<Range<usize>>,
+    /// Byte offset of the end of the case.
+    pub end: usize,
+}
+
+/// Lists the test cases in a file.
+///
+/// A case starts with a header and lasts until the next header.
+/// The header is either a line like `=== name`, or the name between two lines of `=`
+/// as in tree-sitter corpus files.
+/// The case is split into parts, typically the input and the expected output,
+/// by lines of `-` (three or more).
+/// Text before the first header is ignored.
+///
+/// # Example
+///
+/// ```rust
+/// # use testdata_rt::parse_delimited;
+/// let src = "=== Hello, world!\nfoo\n---\nFOO\n\n=====\nBar\n=====\n\nbar\n\n---\n\nBAR\n";
+/// let cases = parse_delimited(src);
+/// assert_eq!(cases.len(), 2);
+/// assert_eq!(cases[0].slug, "hello-world");
+/// assert_eq!(&src[cases[0].parts[1].clone()], "FOO\n");
+/// assert_eq!(cases[1].name, "Bar");
+/// assert_eq!(&src[cases[1].parts[0].clone()], "bar\n");
+/// ```
+pub fn parse_delimited(src: &str) -> Vec<DelimitedCase> {
+    let mut lines = Vec::new();
+    let mut pos = 0;
+    for line in src.split_inclusive('\n') {
+        lines.push((pos, line));
+        pos += line.len();
+    }
+
+    let mut cases = Vec::<DelimitedCase>::new();
+    let mut slugs = HashMap::<String, usize>::new();
+    // Current case and the start of the current part
+    let mut current: Option<(DelimitedCase, usize)> = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let (line_start, line) = lines[i];
+        let header = if let Some(name) = short_header(line) {
+            Some((name, 1))
+        } else if is_rule(line, '=') && i + 2 < lines.len() && is_rule(lines[i + 2].1, '=') {
+            Some((lines[i + 1].1.trim(), 3))
+        } else {
+            None
+        };
+        if let Some((name, len)) = header {
+            if let Some((mut case, part_start)) = current.take() {
+                case.parts
+                    .push(trim_blank_lines(src, part_start..line_start));
+                case.end = line_start;
+                cases.push(case);
+            }
+            i += len;
+            let body_start = lines.get(i).map_or(src.len(), |&(pos, _)| pos);
+            let case = DelimitedCase {
+                name: name.to_owned(),
+                slug: unique_slug(&mut slugs, name),
+                parts: Vec::new(),
+                end: src.len(),
+            };
+            current = Some((case, body_start));
+            continue;
+        }
+        if let Some((case, part_start)) = &mut current {
+            if is_rule(line, '-') {
+                case.parts
+                    .push(trim_blank_lines(src, *part_start..line_start));
+                *part_start = line_start + line.len();
+            }
+        }
+        i += 1;
+    }
+    if let Some((mut case, part_start)) = current {
+        case.parts
+            .push(trim_blank_lines(src, part_start..src.len()));
+        cases.push(case);
+    }
+    cases
+}
+
+/// Recognizes `=== name`, optionally followed by `===`.
+fn short_header(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("===")?.trim_start_matches('=');
+    if !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let name = rest.trim().trim_end_matches('=').trim_end();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Recognizes a line consisting of three or more `ch`.
+fn is_rule(line: &str, ch: char) -> bool {
+    let line = line.trim_end();
+    line.len() >= 3 && line.chars().all(|c| c == ch)
+}
+
+/// Shrinks the range to exclude the leading and trailing blank lines.
+///
+/// An empty part is placed after the first line break, if any.
+fn trim_blank_lines(src: &str, range: Range<usize>) -> Range<usize> {
+    let mut trimmed: Option<Range<usize>> = None;
+    let mut first_line_end = None;
+    let mut pos = range.start;
+    for line in src[range.clone()].split_inclusive('\n') {
+        let line_end = pos + line.len();
+        first_line_end.get_or_insert(line_end);
+        if !line.trim().is_empty() {
+            let start = trimmed.as_ref().map_or(pos, |trimmed| trimmed.start);
+            trimmed = Some(start..line_end);
+        }
+        pos = line_end;
+    }
+    trimmed.unwrap_or_else(|| {
+        let at = first_line_end.unwrap_or(range.start);
+        at..at
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_delimited() {
+        let src = "\
+Intro
+
+================
+Tree-sitter style
+================
+
+foo
+
+---
+
+(foo)
+
+=== Short style ===
+bar
+---
+---
+=== Short style
+baz
+===not a header
+";
+        let cases = parse_delimited(src);
+        let summary = cases
+            .iter()
+            .map(|case| {
+                let parts = case
+                    .parts
+                    .iter()
+                    .map(|part| &src[part.clone()])
+                    .collect::<Vec<_>>();
+                (&case.name[..], &case.slug[..], parts)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "Tree-sitter style",
+                    "tree-sitter-style",
+                    vec!["foo\n", "(foo)\n"]
+                ),
+                ("Short style", "short-style", vec!["bar\n", "", ""]),
+                (
+                    "Short style",
+                    "short-style-1",
+                    vec!["baz\n===not a header\n"]
+                ),
+            ]
+        );
+        assert_eq!(cases[0].end, src.find("=== Short").unwrap());
+        assert_eq!(
+            cases[1].parts[1],
+            src.find("---\n---").unwrap() + 4..src.find("---\n---").unwrap() + 4
+        );
+    }
+
+    #[test]
+    fn test_trim_blank_lines() {
+        let src = "\n\nfoo\n\nbar\n \n";
+        assert_eq!(trim_blank_lines(src, 0..src.len()), 2..11);
+        assert_eq!(trim_blank_lines(src, 0..2), 1..1);
+        assert_eq!(trim_blank_lines(src, 5..5), 5..5);
+    }
+}
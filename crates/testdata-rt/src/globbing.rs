@@ -1,4 +1,6 @@
 use std::collections::HashSet;
+use std::fs;
+use std::io;
 use std::path::{Path, PathBuf, StripPrefixError};
 
 use path_slash::PathBufExt as _;
@@ -6,7 +8,12 @@ use path_slash::PathExt as _;
 use thiserror::Error as StdError;
 use walkdir::WalkDir;
 
+use crate::compression::Compression;
+use crate::delimited::parse_delimited;
+use crate::markdown::parse_markdown;
+use crate::overlay::{is_whiteout, whiteout_path};
 use crate::patterns::{GlobParseError, GlobPattern};
+use crate::review::{is_pending_review, is_review_manifest};
 
 /// Represents the glob error.
 #[derive(Debug, StdError)]
@@ -17,16 +24,22 @@ pub enum GlobError {
     StripPrefix(#[source] StripPrefixError, PathBuf, PathBuf),
     #[error("Got a non-utf8 path: {0:?}")]
     InvalidPath(PathBuf),
+    #[error("Cannot read {}: {}", .1.display(), .0)]
+    Read(#[source] io::Error, PathBuf),
 }
 
 /// Configurations for finding test files in a file-based test.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct GlobSpec {
     /// Serching root. Defaults to `.`.
     pub root: PathBuf,
     /// List of arguments in the order of appearence.
     pub args: Vec<ArgSpec>,
+    /// Name of the test suite, used as a key in the lock file.
+    pub name: Option<String>,
+    /// Path to the lock file recording the hashes of the input files.
+    pub lock_file: Option<PathBuf>,
 }
 
 impl GlobSpec {
@@ -35,6 +48,8 @@ impl GlobSpec {
         Self {
             root: PathBuf::from("."),
             args: Vec::new(),
+            name: None,
+            lock_file: None,
         }
     }
 
@@ -74,6 +89,30 @@ impl GlobSpec {
         self
     }
 
+    /// Builder utility to set `self.name`.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Builder utility to set `self.lock_file`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::path::Path;
+    /// # use testdata_rt::{GlobSpec, ArgSpec};
+    /// let spec = GlobSpec::new()
+    ///     .name("my_tests::test_foo")
+    ///     .lock_file(Path::new("testdata.lock"))
+    ///     .arg(ArgSpec::new("tests/data/*-in.txt").input());
+    /// assert_eq!(spec.lock_file.as_deref(), Some(Path::new("testdata.lock")));
+    /// ```
+    pub fn lock_file(mut self, lock_file: &Path) -> Self {
+        self.lock_file = Some(lock_file.to_owned());
+        self
+    }
+
     /// Searches for the test files.
     ///
     /// # Example
@@ -97,19 +136,34 @@ impl GlobSpec {
             let walk_root = root.join(PathBuf::from_slash(prefix));
             for entry in WalkDir::new(&walk_root).sort_by_file_name() {
                 let entry = entry?;
+                let is_review = entry.file_type().is_file()
+                    && (is_pending_review(entry.path()) || is_review_manifest(entry.path()));
+                if is_whiteout(entry.path()) || is_review {
+                    continue;
+                }
                 let file_name = entry.path().strip_prefix(&root).map_err(|e| {
                     GlobError::StripPrefix(e, root.clone(), entry.path().to_owned())
                 })?;
                 let file_name = file_name
                     .to_slash()
                     .ok_or_else(|| GlobError::InvalidPath(entry.path().to_owned()))?;
+                // foo-out.txt.zst is found as foo-out.txt
+                let uncompressed_name = Compression::strip_extension(&file_name)
+                    .map(|(uncompressed_name, _)| uncompressed_name);
                 for arg in &self.args {
-                    for stem in arg.glob.do_match(&file_name) {
-                        stems.insert(stem.to_owned());
+                    for file_name in Some(&*file_name).into_iter().chain(uncompressed_name) {
+                        for stem in arg.glob.do_match(file_name) {
+                            if !is_whited_out(&root, arg, stem, file_name) {
+                                stems.insert(stem.to_owned());
+                            }
+                        }
                     }
                 }
             }
         }
+        if self.has_blocks() || self.has_parts() {
+            stems = self.case_stems(cwd, stems)?;
+        }
         let sorted_stems = {
             let mut sorted_stems = stems.into_iter().collect::<Vec<_>>();
             sorted_stems.sort();
@@ -119,8 +173,78 @@ impl GlobSpec {
         Ok(sorted_stems)
     }
 
+    /// Whether the test cases are sections of Markdown documents (see [`ArgSpec::block`]).
+    pub fn has_blocks(&self) -> bool {
+        self.args.iter().any(|arg| arg.block.is_some())
+    }
+
+    /// Whether the test cases are parts of files delimited by headers (see [`ArgSpec::part`]).
+    pub fn has_parts(&self) -> bool {
+        self.args.iter().any(|arg| arg.part.is_some())
+    }
+
+    /// Splits a stem like `spec#hello-world` into the file part and the slug of the section.
+    pub fn split_stem<'a>(&self, stem: &'a str) -> (&'a str, Option<&'a str>) {
+        match stem.split_once('#') {
+            Some((file_stem, slug)) if self.has_blocks() || self.has_parts() => {
+                (file_stem, Some(slug))
+            }
+            _ => (stem, None),
+        }
+    }
+
+    /// Turns each file stem into the stems of the test cases in the file,
+    /// either the sections of the Markdown document or the delimited cases.
+    fn case_stems(
+        &self,
+        cwd: &Path,
+        file_stems: HashSet<String>,
+    ) -> Result<HashSet<String>, GlobError> {
+        let case_arg = self
+            .args
+            .iter()
+            .position(|arg| arg.block.is_some() || arg.part.is_some())
+            .unwrap();
+        let mut stems = HashSet::new();
+        for file_stem in file_stems {
+            let paths = if let Some(mut paths) = self.expand_core(&file_stem) {
+                paths.swap_remove(case_arg)
+            } else {
+                continue;
+            };
+            let path = if let Some(path) = paths
+                .iter()
+                .map(|path| cwd.join(path))
+                .find(|path| path.is_file())
+            {
+                path
+            } else {
+                continue;
+            };
+            let src = fs::read_to_string(&path).map_err(|e| GlobError::Read(e, path.clone()))?;
+            let slugs = if self.args[case_arg].block.is_some() {
+                parse_markdown(&src)
+                    .into_iter()
+                    .map(|case| case.slug)
+                    .collect::<Vec<_>>()
+            } else {
+                parse_delimited(&src)
+                    .into_iter()
+                    .map(|case| case.slug)
+                    .collect::<Vec<_>>()
+            };
+            for slug in slugs {
+                stems.insert(format!("{}#{}", file_stem, slug));
+            }
+        }
+        Ok(stems)
+    }
+
     /// Assigns a specific test name to get the path(s) to the file.
+    ///
+    /// For Markdown or delimited test cases, the section part of the stem is ignored.
     pub fn expand_core(&self, stem: &str) -> Option<Vec<Vec<PathBuf>>> {
+        let (stem, _) = self.split_stem(stem);
         let mut test_files = Vec::new();
         for arg in &self.args {
             let paths = arg
@@ -168,11 +292,38 @@ impl GlobSpec {
     }
 }
 
+/// Whether the file matched as `stem` is hidden by a whiteout marker in an upper layer.
+fn is_whited_out(root: &Path, arg: &ArgSpec, stem: &str, file_name: &str) -> bool {
+    arg.glob
+        .subst(stem)
+        .iter()
+        .take_while(|layer| *layer != file_name)
+        .any(|layer| whiteout_path(&root.join(PathBuf::from_slash(layer))).exists())
+}
+
+impl Default for GlobSpec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Configuration for a specific argument in a file-based test.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct ArgSpec {
     pub glob: GlobPattern,
+    /// Name of the argument, if known.
+    pub name: Option<String>,
+    /// Compression to store the file with when written.
+    pub compression: Option<Compression>,
+    /// Whether the argument is an input, whose hashes are recorded in the lock file.
+    pub input: bool,
+    /// Index of the code block in the section, if the file is a Markdown document
+    /// whose sections are test cases.
+    pub block: Option<usize>,
+    /// Index of the part in the test case, if the file contains multiple test cases
+    /// delimited by headers.
+    pub part: Option<usize>,
 }
 
 impl ArgSpec {
@@ -183,6 +334,72 @@ impl ArgSpec {
     pub fn parse(glob: &str) -> Result<Self, GlobParseError> {
         Ok(Self {
             glob: glob.parse()?,
+            name: None,
+            compression: None,
+            input: false,
+            block: None,
+            part: None,
         })
     }
+
+    /// Builder utility to set `self.name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use testdata_rt::ArgSpec;
+    /// let arg = ArgSpec::new("tests/data/*-in.txt").name("input");
+    /// assert_eq!(arg.name.as_deref(), Some("input"));
+    /// ```
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Builder utility to set `self.compression`.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Builder utility to set `self.input`.
+    pub fn input(mut self) -> Self {
+        self.input = true;
+        self
+    }
+
+    /// Builder utility to set `self.block`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use testdata_rt::{ArgSpec, GlobSpec};
+    /// let spec = GlobSpec::new()
+    ///     .arg(ArgSpec::new("tests/spec/*.md").block(0))
+    ///     .arg(ArgSpec::new("tests/spec/*.md").block(1));
+    /// assert_eq!(spec.split_stem("commonmark#tabs"), ("commonmark", Some("tabs")));
+    /// ```
+    pub fn block(mut self, index: usize) -> Self {
+        self.block = Some(index);
+        self
+    }
+
+    /// Builder utility to set `self.part`.
+    ///
+    /// Each test case in the file starts with a header like `=== name`
+    /// and its parts are separated by `---` lines (see [`parse_delimited`](crate::parse_delimited)).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use testdata_rt::{ArgSpec, GlobSpec};
+    /// let spec = GlobSpec::new()
+    ///     .arg(ArgSpec::new("tests/corpus/*.txt").part(0))
+    ///     .arg(ArgSpec::new("tests/corpus/*.txt").part(1));
+    /// assert_eq!(spec.split_stem("expressions#binary"), ("expressions", Some("binary")));
+    /// ```
+    pub fn part(mut self, index: usize) -> Self {
+        self.part = Some(index);
+        self
+    }
 }
@@ -2,10 +2,28 @@
 
 #![cfg_attr(all(feature = "__doc_cfg", doc), feature(doc_cfg))]
 
+mod compression;
+mod delimited;
 mod globbing;
+mod markdown;
+mod overlay;
 mod patterns;
+#[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
+mod records;
+mod review;
 
+pub use crate::compression::{Compression, CompressionParseError};
+pub use crate::delimited::{parse_delimited, DelimitedCase};
 pub use crate::globbing::{ArgSpec, GlobError, GlobSpec};
+pub use crate::markdown::{parse_markdown, slugify, CodeBlock, MarkdownCase};
+pub use crate::overlay::{is_whiteout, whiteout_path, WHITEOUT_PREFIX};
 pub use crate::patterns::{GlobParseError, GlobPattern};
+#[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "json")))]
+pub use crate::records::{Record, RecordError, RecordSpec, RecordValue};
+pub use crate::review::{
+    is_pending_review, is_review_manifest, read_review_manifest, review_manifest_path, review_path,
+    reviewed_path, write_review_manifest, REVIEW_MANIFEST, REVIEW_SUFFIX,
+};
 #[doc(hidden)]
 pub extern crate pretty_assertions;
@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// A section of a Markdown document containing code blocks, used as a test case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownCase {
+    /// The heading text, like `Hello, world!`.
+    pub heading: String,
+    /// The slug made from the heading, like `hello-world`. Unique in the document.
+    pub slug: String,
+    /// The fenced code blocks in the section, in the order of appearance.
+    pub blocks: Vec<CodeBlock>,
+    /// Byte offset of the end of the section.
+    pub end: usize,
+}
+
+/// A fenced code block in a Markdown document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// The info string after the opening fence, like `rust`.
+    pub info: String,
+    /// Byte range of the contents, between the fences.
+    pub contents: Range<usize>,
+    /// Byte offset of the end of the closing fence line.
+    pub end: usize,
+}
+
+/// Lists the sections with code blocks.
+///
+/// A section starts with an ATX heading (`#` to `######`) and lasts until the next heading.
+/// Code blocks before the first heading are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// # use testdata_rt::parse_markdown;
+/// let src = "# Spec\n\n## Hello, world!\n\n```\nfoo\n```\n\n```\nFOO\n```\n";
+/// let cases = parse_markdown(src);
+/// assert_eq!(cases.len(), 1);
+/// assert_eq!(cases[0].slug, "hello-world");
+/// assert_eq!(&src[cases[0].blocks[1].contents.clone()], "FOO\n");
+/// ```
+pub fn parse_markdown(src: &str) -> Vec<MarkdownCase> {
+    let mut cases = Vec::<MarkdownCase>::new();
+    let mut slugs = HashMap::<String, usize>::new();
+    // Current section and the open fence: (fence, info, contents start)
+    let mut current: Option<MarkdownCase> = None;
+    let mut fence: Option<(String, String, usize)> = None;
+    let mut pos = 0;
+    for line in src.split_inclusive('\n') {
+        let line_start = pos;
+        pos += line.len();
+        if let Some((open, info, start)) = &fence {
+            if is_closing_fence(line, open) {
+                if let Some(current) = &mut current {
+                    current.blocks.push(CodeBlock {
+                        info: info.clone(),
+                        contents: *start..line_start,
+                        end: pos,
+                    });
+                }
+                fence = None;
+            }
+            continue;
+        }
+        if let Some((open, info)) = opening_fence(line) {
+            fence = Some((open, info, pos));
+        } else if let Some(heading) = heading(line) {
+            if let Some(mut case) = current.take() {
+                case.end = line_start;
+                cases.push(case);
+            }
+            current = Some(MarkdownCase {
+                heading: heading.to_owned(),
+                slug: unique_slug(&mut slugs, heading),
+                blocks: Vec::new(),
+                end: src.len(),
+            });
+        }
+    }
+    if let Some((_, info, start)) = fence {
+        // An unclosed block lasts until the end of the document
+        if let Some(current) = &mut current {
+            current.blocks.push(CodeBlock {
+                info,
+                contents: start..src.len(),
+                end: src.len(),
+            });
+        }
+    }
+    cases.extend(current);
+    cases.retain(|case| !case.blocks.is_empty());
+    cases
+}
+
+/// Makes a slug from a heading, in the way similar to GitHub.
+///
+/// # Example
+///
+/// ```rust
+/// # use testdata_rt::slugify;
+/// assert_eq!(slugify("Hello, World!"), "hello-world");
+/// assert_eq!(slugify("Tabs\tand  spaces"), "tabs-and--spaces");
+/// ```
+pub fn slugify(heading: &str) -> String {
+    heading
+        .trim()
+        .chars()
+        .filter_map(|ch| {
+            if ch.is_alphanumeric() || ch == '_' || ch == '-' {
+                Some(ch.to_lowercase().next().unwrap_or(ch))
+            } else if ch.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Makes a slug, adding a suffix like `-1` if already used in the document.
+pub(crate) fn unique_slug(slugs: &mut HashMap<String, usize>, heading: &str) -> String {
+    let slug = slugify(heading);
+    let count = slugs.entry(slug.clone()).or_insert(0);
+    let slug = if *count > 0 {
+        format!("{}-{}", slug, count)
+    } else {
+        slug
+    };
+    *count += 1;
+    slug
+}
+
+/// Recognizes `## Heading ##`.
+fn heading(line: &str) -> Option<&str> {
+    let line = strip_indent(line)?.trim_end();
+    let level = line.len() - line.trim_start_matches('#').len();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    Some(rest.trim().trim_end_matches('#').trim_end())
+}
+
+/// Recognizes ```` ```info ```` or `~~~info`.
+fn opening_fence(line: &str) -> Option<(String, String)> {
+    let line = strip_indent(line)?.trim_end();
+    let ch = line.chars().next().filter(|&ch| ch == '`' || ch == '~')?;
+    let len = line.len() - line.trim_start_matches(ch).len();
+    if len < 3 {
+        return None;
+    }
+    let info = line[len..].trim();
+    if ch == '`' && info.contains('`') {
+        return None;
+    }
+    Some((line[..len].to_owned(), info.to_owned()))
+}
+
+fn is_closing_fence(line: &str, open: &str) -> bool {
+    let ch = open.chars().next().unwrap();
+    match strip_indent(line) {
+        Some(line) => {
+            let line = line.trim_end();
+            let len = line.len() - line.trim_start_matches(ch).len();
+            len >= open.len() && len == line.len()
+        }
+        None => false,
+    }
+}
+
+/// Strips the indentation of up to three spaces.
+fn strip_indent(line: &str) -> Option<&str> {
+    let stripped = line.trim_start_matches(' ');
+    (line.len() - stripped.len() <= 3).then_some(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markdown() {
+        let src = "\
+Intro
+
+```
+ignored
+```
+
+# Uppercase
+
+Some text.
+
+```text
+foo
+```
+
+````markdown
+```
+FOO
+```
+````
+
+## Empty
+
+# Uppercase
+
+~~~
+bar
+";
+        let cases = parse_markdown(src);
+        let summary = cases
+            .iter()
+            .map(|case| {
+                let blocks = case
+                    .blocks
+                    .iter()
+                    .map(|block| (&block.info[..], &src[block.contents.clone()]))
+                    .collect::<Vec<_>>();
+                (&case.slug[..], blocks)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "uppercase",
+                    vec![("text", "foo\n"), ("markdown", "```\nFOO\n```\n")]
+                ),
+                ("uppercase-1", vec![("", "bar\n")]),
+            ]
+        );
+        assert_eq!(cases[0].end, src.find("## Empty").unwrap());
+        assert_eq!(cases[0].blocks[0].end, src.find("```\n\n````").unwrap() + 4);
+    }
+
+    #[test]
+    fn test_heading() {
+        assert_eq!(heading("# Foo\n"), Some("Foo"));
+        assert_eq!(heading("   ### Foo ###\n"), Some("Foo"));
+        assert_eq!(heading("#Foo\n"), None);
+        assert_eq!(heading("    # Foo\n"), None);
+        assert_eq!(heading("####### Foo\n"), None);
+    }
+}
@@ -0,0 +1,33 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// File name prefix of a whiteout marker.
+///
+/// A whiteout marker `.wh.foo-out.txt` in a layer hides `foo-out.txt`
+/// in the lower layers of a comma-separated glob.
+pub const WHITEOUT_PREFIX: &str = ".wh.";
+
+/// Returns the path to the whiteout marker hiding `path` in the lower layers.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::path::Path;
+/// # use testdata_rt::whiteout_path;
+/// assert_eq!(
+///     whiteout_path(Path::new("tests/fixtures/foo-out.txt")),
+///     Path::new("tests/fixtures/.wh.foo-out.txt"),
+/// );
+/// ```
+pub fn whiteout_path(path: &Path) -> PathBuf {
+    let mut file_name = OsString::from(WHITEOUT_PREFIX);
+    file_name.push(path.file_name().unwrap_or_default());
+    path.with_file_name(file_name)
+}
+
+/// Checks if the path refers to a whiteout marker.
+pub fn is_whiteout(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|file_name| file_name.to_str())
+        .is_some_and(|file_name| file_name.starts_with(WHITEOUT_PREFIX))
+}
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error as StdError;
+
+#[derive(Debug, StdError)]
+pub enum RecordError {
+    #[error("Cannot read {}: {}", .1.display(), .0)]
+    Read(#[source] io::Error, PathBuf),
+    #[error("Unknown record format (expected .jsonl, .ndjson or .csv): {}", .0.display())]
+    UnknownFormat(PathBuf),
+    #[error("{}:{}: {}", .path.display(), .line, .message)]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+/// Configurations for reading test cases from records in a file.
+///
+/// JSON Lines (`.jsonl` or `.ndjson`), one object per line,
+/// and CSV (`.csv`) with a header row are supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RecordSpec {
+    /// Base directory of `file`. Defaults to `.`.
+    pub root: PathBuf,
+    /// Path to the file containing the records.
+    pub file: PathBuf,
+    /// Name of the field used to name the test cases.
+    /// If absent, the cases are named after the line numbers, like `line_3`.
+    pub key: Option<String>,
+}
+
+/// A record read from the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// Name of the test case. Unique in the file.
+    pub name: String,
+    /// 1-based line number where the record starts.
+    pub line: usize,
+    /// Fields in the order of appearance.
+    pub fields: Vec<(String, RecordValue)>,
+}
+
+/// A field value of a [`Record`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordValue {
+    /// A value in JSON Lines.
+    Json(serde_json::Value),
+    /// A value in CSV, to be parsed according to the type it is read as.
+    Text(String),
+}
+
+impl Record {
+    /// Returns the field of the name.
+    pub fn get(&self, name: &str) -> Option<&RecordValue> {
+        self.fields
+            .iter()
+            .find(|(field_name, _)| field_name == name)
+            .map(|(_, value)| value)
+    }
+}
+
+impl RecordSpec {
+    /// Creates the configuration for the file.
+    pub fn new(file: &Path) -> Self {
+        Self {
+            root: PathBuf::from("."),
+            file: file.to_owned(),
+            key: None,
+        }
+    }
+
+    /// Builder utility to set `self.root`.
+    pub fn root(mut self, root: &Path) -> Self {
+        self.root = root.to_owned();
+        self
+    }
+
+    /// Builder utility to set `self.key`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::path::Path;
+    /// # use testdata_rt::RecordSpec;
+    /// let spec = RecordSpec::new(Path::new("tests/cases.csv")).key("name");
+    /// assert_eq!(spec.key.as_deref(), Some("name"));
+    /// ```
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_owned());
+        self
+    }
+
+    /// Returns the path to the file.
+    pub fn path(&self) -> PathBuf {
+        self.root.join(&self.file)
+    }
+
+    /// Reads the records.
+    pub fn read(&self) -> Result<Vec<Record>, RecordError> {
+        self.read_from(Path::new(""))
+    }
+
+    /// Reads the records, with custom working directory.
+    pub fn read_from(&self, cwd: &Path) -> Result<Vec<Record>, RecordError> {
+        let path = cwd.join(self.path());
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        let parse = match extension {
+            Some("jsonl" | "ndjson") => parse_json_lines,
+            Some("csv") => parse_csv,
+            _ => return Err(RecordError::UnknownFormat(path)),
+        };
+        let src = fs::read_to_string(&path).map_err(|e| RecordError::Read(e, path.clone()))?;
+        let rows = parse(&src).map_err(|(line, message)| RecordError::Parse {
+            path: path.clone(),
+            line,
+            message,
+        })?;
+
+        let mut names = HashMap::<String, usize>::new();
+        let mut records = Vec::new();
+        for (line, fields) in rows {
+            let mut record = Record {
+                name: String::new(),
+                line,
+                fields,
+            };
+            let name = match &self.key {
+                Some(key) => match record.get(key) {
+                    Some(RecordValue::Json(serde_json::Value::String(s))) => s.clone(),
+                    Some(RecordValue::Json(
+                        value @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_)),
+                    )) => value.to_string(),
+                    Some(RecordValue::Text(s)) if !s.is_empty() => s.clone(),
+                    _ => {
+                        return Err(RecordError::Parse {
+                            path,
+                            line,
+                            message: format!("Missing or invalid key field {:?}", key),
+                        })
+                    }
+                },
+                None => format!("line_{}", line),
+            };
+            let count = names.entry(name.clone()).or_insert(0);
+            record.name = if *count > 0 {
+                format!("{}-{}", name, count)
+            } else {
+                name
+            };
+            *count += 1;
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    /// Finds the record of the name.
+    pub fn find(&self, name: &str) -> Result<Option<Record>, RecordError> {
+        Ok(self.read()?.into_iter().find(|record| record.name == name))
+    }
+}
+
+/// Rows with the line numbers.
+type Rows<T> = Vec<(usize, Vec<T>)>;
+/// Line number and message.
+type ParseError = (usize, String);
+
+/// Reads one JSON object per non-empty line.
+fn parse_json_lines(src: &str) -> Result<Rows<(String, RecordValue)>, ParseError> {
+    let mut rows = Vec::new();
+    for (i, line) in src.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value =
+            serde_json::from_str::<serde_json::Value>(line).map_err(|e| (i + 1, e.to_string()))?;
+        let object = match value {
+            serde_json::Value::Object(object) => object,
+            _ => return Err((i + 1, "Expected a JSON object".to_owned())),
+        };
+        let fields = object
+            .into_iter()
+            .map(|(name, value)| (name, RecordValue::Json(value)))
+            .collect();
+        rows.push((i + 1, fields));
+    }
+    Ok(rows)
+}
+
+/// Reads CSV as in RFC 4180, taking the first row as the header.
+fn parse_csv(src: &str) -> Result<Rows<(String, RecordValue)>, ParseError> {
+    let mut rows = csv_rows(src)?.into_iter();
+    let header = match rows.next() {
+        Some((_, header)) => header,
+        None => return Ok(Vec::new()),
+    };
+    rows.map(|(line, row)| {
+        if row.len() != header.len() {
+            return Err((
+                line,
+                format!("Expected {} fields, found {}", header.len(), row.len()),
+            ));
+        }
+        let fields = header
+            .iter()
+            .cloned()
+            .zip(row.into_iter().map(RecordValue::Text))
+            .collect();
+        Ok((line, fields))
+    })
+    .collect()
+}
+
+/// Splits CSV into rows of fields, along with the line numbers. Blank lines are skipped.
+fn csv_rows(src: &str) -> Result<Rows<String>, ParseError> {
+    let mut rows = Vec::new();
+    let mut chars = src.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let row_line = line;
+        let mut row = Vec::new();
+        let mut field = String::new();
+        loop {
+            match chars.next() {
+                Some('"') if field.is_empty() => loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(ch) => {
+                            if ch == '\n' {
+                                line += 1;
+                            }
+                            field.push(ch);
+                        }
+                        None => return Err((row_line, "Unterminated quoted field".to_owned())),
+                    }
+                },
+                Some(',') => row.push(std::mem::take(&mut field)),
+                Some('\r') if chars.peek() == Some(&'\n') => {}
+                Some('\n') | None => {
+                    line += 1;
+                    row.push(field);
+                    break;
+                }
+                Some(ch) => field.push(ch),
+            }
+        }
+        if row.len() > 1 || !row[0].is_empty() {
+            rows.push((row_line, row));
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_rows() {
+        let src = "a,b\n1,\"x, \"\"y\"\"\"\n\n2,\"multi\nline\"\r\n3,\n";
+        assert_eq!(
+            csv_rows(src).unwrap(),
+            vec![
+                (1, vec!["a".to_owned(), "b".to_owned()]),
+                (2, vec!["1".to_owned(), "x, \"y\"".to_owned()]),
+                (4, vec!["2".to_owned(), "multi\nline".to_owned()]),
+                (6, vec!["3".to_owned(), "".to_owned()]),
+            ]
+        );
+        assert_eq!(
+            csv_rows("a\n\"b\n").unwrap_err(),
+            (2, "Unterminated quoted field".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_read_records() {
+        let dir = std::env::temp_dir().join(format!("testdata-rt-records-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("cases.jsonl"),
+            "{\"name\": \"foo\", \"a\": 1}\n\n{\"name\": \"foo\", \"a\": 2}\n{\"a\": 3}\n",
+        )
+        .unwrap();
+
+        let spec = RecordSpec::new(Path::new("cases.jsonl"));
+        let records = spec.read_from(&dir).unwrap();
+        let names = records.iter().map(|r| &r.name[..]).collect::<Vec<_>>();
+        assert_eq!(names, vec!["line_1", "line_3", "line_4"]);
+        assert_eq!(
+            records[1].get("a"),
+            Some(&RecordValue::Json(serde_json::json!(2)))
+        );
+
+        let e = spec.clone().key("name").read_from(&dir).unwrap_err();
+        assert!(
+            e.to_string()
+                .ends_with("cases.jsonl:4: Missing or invalid key field \"name\""),
+            "{}",
+            e
+        );
+
+        fs::write(dir.join("cases.csv"), "name,a\nfoo,1\nfoo,2\n").unwrap();
+        let spec = RecordSpec::new(Path::new("cases.csv")).key("name");
+        let records = spec.read_from(&dir).unwrap();
+        let names = records.iter().map(|r| &r.name[..]).collect::<Vec<_>>();
+        assert_eq!(names, vec!["foo", "foo-1"]);
+        assert_eq!(
+            records[1].get("a"),
+            Some(&RecordValue::Text("2".to_owned()))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name suffix of a snapshot pending review.
+///
+/// In review mode, a mismatching `foo-out.txt` is written as `foo-out.txt.new`
+/// until it is accepted or rejected.
+pub const REVIEW_SUFFIX: &str = ".new";
+
+/// File name of the manifest recording the snapshots pending review in the directory.
+///
+/// Only the files listed there are pending, so that a fixture which happens
+/// to end with [`REVIEW_SUFFIX`] is not mistaken for one.
+pub const REVIEW_MANIFEST: &str = ".testdata-review";
+
+/// Returns the path to the snapshot pending review in place of `path`.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::path::Path;
+/// # use testdata_rt::review_path;
+/// assert_eq!(
+///     review_path(Path::new("tests/fixtures/foo-out.txt")),
+///     Path::new("tests/fixtures/foo-out.txt.new"),
+/// );
+/// ```
+pub fn review_path(path: &Path) -> PathBuf {
+    let mut file_name = OsString::from(path.file_name().unwrap_or_default());
+    file_name.push(REVIEW_SUFFIX);
+    path.with_file_name(file_name)
+}
+
+/// Returns the path the snapshot pending review replaces, if it is one.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::path::Path;
+/// # use testdata_rt::reviewed_path;
+/// assert_eq!(
+///     reviewed_path(Path::new("tests/fixtures/foo-out.txt.new")).as_deref(),
+///     Some(Path::new("tests/fixtures/foo-out.txt")),
+/// );
+/// assert_eq!(reviewed_path(Path::new("tests/fixtures/foo-out.txt")), None);
+/// ```
+pub fn reviewed_path(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?.to_str()?;
+    let file_name = file_name.strip_suffix(REVIEW_SUFFIX)?;
+    (!file_name.is_empty()).then(|| path.with_file_name(file_name))
+}
+
+/// Returns the path to the manifest next to the snapshot pending review.
+pub fn review_manifest_path(path: &Path) -> PathBuf {
+    path.with_file_name(REVIEW_MANIFEST)
+}
+
+/// Returns whether the path is the manifest of the snapshots pending review.
+pub fn is_review_manifest(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|file_name| file_name == REVIEW_MANIFEST)
+}
+
+/// Reads the file names listed in the manifest, which are empty if it does not exist.
+pub fn read_review_manifest(manifest: &Path) -> io::Result<BTreeSet<String>> {
+    match fs::read_to_string(manifest) {
+        Ok(contents) => Ok(contents.lines().map(str::to_owned).collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(BTreeSet::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes the file names to the manifest, or removes it if there are none.
+pub fn write_review_manifest(manifest: &Path, names: &BTreeSet<String>) -> io::Result<()> {
+    if names.is_empty() {
+        return match fs::remove_file(manifest) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        };
+    }
+    let contents = names
+        .iter()
+        .map(|name| format!("{}\n", name))
+        .collect::<String>();
+    fs::write(manifest, contents)
+}
+
+/// Returns whether the file is a snapshot pending review, that is,
+/// it is listed in the manifest of the directory.
+pub fn is_pending_review(path: &Path) -> bool {
+    let file_name = match path.file_name().and_then(|file_name| file_name.to_str()) {
+        Some(file_name) if reviewed_path(path).is_some() => file_name,
+        _ => return false,
+    };
+    read_review_manifest(&review_manifest_path(path)).is_ok_and(|names| names.contains(file_name))
+}
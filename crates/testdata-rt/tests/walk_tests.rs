@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use testdata_rt::{ArgSpec, GlobError, GlobSpec};
+use testdata_rt::{ArgSpec, GlobError, GlobSpec, REVIEW_MANIFEST};
 
 #[test]
 fn test_walk_dir() {
@@ -14,6 +14,7 @@ fn test_walk_dir() {
         vec![
             "bar".to_owned(),
             "baz".to_owned(),
+            "compressed".to_owned(),
             "foo".to_owned(),
             "nested/bar".to_owned(),
             "nested/baz".to_owned(),
@@ -33,6 +34,7 @@ fn test_walk_dir2() {
         vec![
             "bar".to_owned(),
             "baz".to_owned(),
+            "compressed".to_owned(),
             "foo".to_owned(),
             "nested/bar".to_owned(),
             "nested/baz".to_owned(),
@@ -50,7 +52,12 @@ fn test_walk_dir_non_nested() {
     let stems = spec.glob().unwrap();
     assert_eq!(
         stems,
-        vec!["bar".to_owned(), "baz".to_owned(), "foo".to_owned(),]
+        vec![
+            "bar".to_owned(),
+            "baz".to_owned(),
+            "compressed".to_owned(),
+            "foo".to_owned(),
+        ]
     );
 }
 
@@ -61,6 +68,32 @@ fn test_walk_dir_no_args() {
     assert_eq!(stems, vec![] as Vec<String>);
 }
 
+#[test]
+fn test_walk_skips_pending_review() {
+    // Not a fixture, which `cargo testdata review` would find
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("walk_review");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("review")).unwrap();
+    for name in ["foo.txt", "bar.txt.new", "baz.txt.new"] {
+        std::fs::write(root.join("review").join(name), "foo\n").unwrap();
+    }
+    std::fs::write(root.join("review").join(REVIEW_MANIFEST), "bar.txt.new\n").unwrap();
+
+    let spec = GlobSpec::new().root(&root).arg(ArgSpec::new("review/*"));
+    let stems = spec.glob().unwrap();
+    // Only the recorded one is pending
+    assert_eq!(stems, vec!["baz.txt.new".to_owned(), "foo.txt".to_owned()]);
+}
+
+#[test]
+fn test_walk_skips_whited_out() {
+    let spec = GlobSpec::new()
+        .root(Path::new("tests/fixtures/overlay"))
+        .arg(ArgSpec::new("upper/*-in.txt,lower/*-in.txt"));
+    let stems = spec.glob().unwrap();
+    assert_eq!(stems, vec!["shown".to_owned(), "top".to_owned()]);
+}
+
 #[test]
 fn test_error_source() {
     use std::error::Error as StdError;
@@ -95,3 +128,48 @@ fn test_arg_spec_debug() {
     let arg_spec = ArgSpec::new("src/**/*.txt");
     let _ = format!("{:?}", arg_spec);
 }
+
+#[test]
+fn test_walk_markdown() {
+    let spec = GlobSpec::new()
+        .root(Path::new("tests/fixtures/markdown"))
+        .arg(ArgSpec::new("*.md").block(0))
+        .arg(ArgSpec::new("*.md").block(1));
+    let stems = spec.glob().unwrap();
+    assert_eq!(
+        stems,
+        vec!["spec#multiple-lines".to_owned(), "spec#simple".to_owned()]
+    );
+    let paths = spec.expand_core("spec#simple").unwrap();
+    assert_eq!(
+        paths,
+        vec![
+            vec![Path::new("tests/fixtures/markdown/spec.md").to_owned()],
+            vec![Path::new("tests/fixtures/markdown/spec.md").to_owned()],
+        ]
+    );
+}
+
+#[test]
+fn test_walk_delimited() {
+    let spec = GlobSpec::new()
+        .root(Path::new("tests/fixtures/delimited"))
+        .arg(ArgSpec::new("*.txt").part(0))
+        .arg(ArgSpec::new("*.txt").part(1));
+    let stems = spec.glob().unwrap();
+    assert_eq!(
+        stems,
+        vec![
+            "corpus#multiple-lines".to_owned(),
+            "corpus#simple".to_owned()
+        ]
+    );
+    let paths = spec.expand_core("corpus#simple").unwrap();
+    assert_eq!(
+        paths,
+        vec![
+            vec![Path::new("tests/fixtures/delimited/corpus.txt").to_owned()],
+            vec![Path::new("tests/fixtures/delimited/corpus.txt").to_owned()],
+        ]
+    );
+}
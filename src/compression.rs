@@ -0,0 +1,87 @@
+use std::ffi::OsString;
+use std::io::{self, Read, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+
+use testdata_rt::Compression;
+
+/// Lists the paths a file may be stored at, starting from the uncompressed one.
+pub(crate) fn variants(path: &Path) -> impl Iterator<Item = (PathBuf, Option<Compression>)> + '_ {
+    iter::once((path.to_owned(), None)).chain(
+        Compression::ALL
+            .iter()
+            .map(move |&compression| (compressed_path(path, compression), Some(compression))),
+    )
+}
+
+/// Finds the path the file is actually stored at.
+pub(crate) fn stored_variant(path: &Path) -> Option<(PathBuf, Option<Compression>)> {
+    variants(path).find(|(path, _)| path.exists())
+}
+
+fn compressed_path(path: &Path, compression: Compression) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(".");
+    path.push(compression.extension());
+    PathBuf::from(path)
+}
+
+pub(crate) fn decoder<'a, R>(
+    compression: Option<Compression>,
+    reader: R,
+) -> io::Result<Box<dyn Read + Send + 'a>>
+where
+    R: Read + Send + 'a,
+{
+    match compression {
+        None => Ok(Box::new(reader)),
+        #[cfg(feature = "gzip")]
+        Some(Compression::Gzip) => Ok(Box::new(flate2::read::MultiGzDecoder::new(reader))),
+        #[cfg(feature = "zstd")]
+        Some(Compression::Zstd) => Ok(Box::new(zstd::Decoder::new(reader)?)),
+        Some(compression) => Err(unsupported(compression)),
+    }
+}
+
+/// Compresses the contents of `reader` into `writer`.
+pub(crate) fn encode_to<R, W>(
+    compression: Option<Compression>,
+    reader: &mut R,
+    writer: W,
+) -> io::Result<u64>
+where
+    R: Read + ?Sized,
+    W: Write,
+{
+    match compression {
+        None => {
+            let mut writer = writer;
+            io::copy(reader, &mut writer)
+        }
+        #[cfg(feature = "gzip")]
+        Some(Compression::Gzip) => {
+            let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::best());
+            let len = io::copy(reader, &mut encoder)?;
+            encoder.finish()?;
+            Ok(len)
+        }
+        #[cfg(feature = "zstd")]
+        Some(Compression::Zstd) => {
+            let mut encoder = zstd::Encoder::new(writer, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+            let len = io::copy(reader, &mut encoder)?;
+            encoder.finish()?;
+            Ok(len)
+        }
+        Some(compression) => Err(unsupported(compression)),
+    }
+}
+
+fn unsupported(compression: Compression) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "Enable the \"{}\" feature of testdata to use {} compression",
+            compression, compression
+        ),
+    )
+}
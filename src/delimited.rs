@@ -0,0 +1,72 @@
+use std::io;
+use std::str;
+
+use testdata_rt::{parse_delimited, DelimitedCase};
+
+/// Extracts the contents of the `index`-th part of the test case.
+pub(crate) fn read_part(src: &[u8], slug: &str, index: usize) -> io::Result<Vec<u8>> {
+    let src = to_str(src)?;
+    let case = find_case(src, slug)?;
+    let part = case.parts.get(index).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Case {:?} has no part #{}", slug, index),
+        )
+    })?;
+    Ok(src[part.clone()].as_bytes().to_owned())
+}
+
+/// Replaces the contents of the `index`-th part of the test case.
+///
+/// If the case has exactly `index` parts, a new part is added after the last one.
+pub(crate) fn write_part(
+    src: &[u8],
+    slug: &str,
+    index: usize,
+    contents: &[u8],
+) -> io::Result<Vec<u8>> {
+    let src = to_str(src)?;
+    let contents = to_str(contents)?;
+    let case = find_case(src, slug)?;
+    let mut contents = contents.to_owned();
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    let mut result = String::with_capacity(src.len() + contents.len());
+    if let Some(part) = case.parts.get(index) {
+        result.push_str(&src[..part.start]);
+        result.push_str(&contents);
+        result.push_str(&src[part.end..]);
+    } else if index == case.parts.len() {
+        let last = case.parts.last().unwrap();
+        result.push_str(&src[..last.end]);
+        if !result.ends_with('\n') {
+            result.push('\n');
+        }
+        result.push_str("---\n");
+        result.push_str(&contents);
+        result.push_str(&src[last.end..]);
+    } else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Case {:?} has only {} parts, cannot add #{}",
+                slug,
+                case.parts.len(),
+                index
+            ),
+        ));
+    }
+    Ok(result.into_bytes())
+}
+
+fn find_case(src: &str, slug: &str) -> io::Result<DelimitedCase> {
+    parse_delimited(src)
+        .into_iter()
+        .find(|case| case.slug == slug)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("No case {:?}", slug)))
+}
+
+fn to_str(data: &[u8]) -> io::Result<&str> {
+    str::from_utf8(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,347 @@
+use std::fmt;
+use std::str;
+
+use thiserror::Error;
+
+use crate::snapshots::{review_note, SnapshotMode};
+use crate::test_files::TestFile;
+
+/// Compares diagnostics against the annotations in the source file, like compiletest.
+///
+/// The actual diagnostics are anything convertible to [`Diagnostic`],
+/// like `(line, level, message)` triples.
+/// Each annotation must match a diagnostic on the same line, with the same level
+/// and with a message containing the annotated one.
+/// See [`parse_annotations`] for the syntax.
+///
+/// The marker defaults to `//~`. Use `marker = "#~"` for languages with other comment styles.
+///
+/// With `UPDATE_SNAPSHOTS=all`, the annotations are rewritten instead.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let diagnostics = lint(&input.read_to_string())
+///     .into_iter()
+///     .map(|d| (d.line, d.level, d.message));
+/// assert_diagnostics!(diagnostics, source = input);
+/// ```
+#[macro_export]
+macro_rules! assert_diagnostics {
+    ($e:expr, source = $test_file:expr) => {
+        $crate::assert_diagnostics_helper($e, &($test_file), "//~")
+    };
+    ($e:expr, source = $test_file:expr, marker = $marker:expr) => {
+        $crate::assert_diagnostics_helper($e, &($test_file), $marker)
+    };
+}
+
+/// A diagnostic reported by the tool under test, or expected by an annotation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Diagnostic {
+    /// 1-based line number.
+    pub line: usize,
+    /// The level, like `ERROR` or `WARNING`.
+    pub level: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(line: usize, level: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            level: level.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Whether the actual diagnostic satisfies this expectation.
+    fn is_matched_by(&self, actual: &Diagnostic) -> bool {
+        self.line == actual.line
+            && normalize_level(&self.level) == normalize_level(&actual.level)
+            && actual.message.contains(&self.message)
+    }
+}
+
+impl<L: Into<String>, M: Into<String>> From<(usize, L, M)> for Diagnostic {
+    fn from((line, level, message): (usize, L, M)) -> Self {
+        Self::new(line, level, message)
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} {}", self.line, self.level, self.message)
+    }
+}
+
+/// An expected diagnostic written as a comment in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub diagnostic: Diagnostic,
+    /// 1-based line number of the comment itself.
+    pub comment_line: usize,
+}
+
+/// Represents an error in parsing annotations.
+#[derive(Debug, Error)]
+pub enum AnnotationError {
+    #[error("Missing level in the annotation at line {line}")]
+    MissingLevel { line: usize },
+    #[error("The annotation at line {line} points before the first line")]
+    OutOfRange { line: usize },
+    #[error("The annotation at line {line} has no previous annotation to follow")]
+    NoPrevious { line: usize },
+}
+
+/// Reads annotations like `//~ ERROR message`, where `//~` is the marker.
+///
+/// - `//~ LEVEL message` refers to the line the comment is on.
+/// - `//~^ LEVEL message` refers to the line above. Each `^` goes up by one line.
+/// - `//~v LEVEL message` refers to the line below. Each `v` goes down by one line.
+/// - `//~| LEVEL message` refers to the same line as the previous annotation.
+///
+/// # Example
+///
+/// ```rust
+/// use testdata::{parse_annotations, Diagnostic};
+///
+/// let src = "let x = 1; //~ WARNING unused\n//~| HELP prefix it\nfoo(); //~^ ERROR\n";
+/// let annotations = parse_annotations(src, "//~").unwrap();
+/// let expected = annotations.into_iter().map(|a| a.diagnostic).collect::<Vec<_>>();
+/// assert_eq!(
+///     expected,
+///     vec![
+///         Diagnostic::new(1, "WARNING", "unused"),
+///         Diagnostic::new(1, "HELP", "prefix it"),
+///         Diagnostic::new(2, "ERROR", ""),
+///     ]
+/// );
+/// ```
+pub fn parse_annotations(src: &str, marker: &str) -> Result<Vec<Annotation>, AnnotationError> {
+    let mut annotations = Vec::<Annotation>::new();
+    for (i, line) in src.lines().enumerate() {
+        let comment_line = i + 1;
+        let rest = if let Some(pos) = line.find(marker) {
+            &line[pos + marker.len()..]
+        } else {
+            continue;
+        };
+        let (target, rest) = if let Some(rest) = rest.strip_prefix('|') {
+            let previous = annotations
+                .last()
+                .ok_or(AnnotationError::NoPrevious { line: comment_line })?;
+            (previous.diagnostic.line, rest)
+        } else if rest.starts_with('^') {
+            let up = rest.len() - rest.trim_start_matches('^').len();
+            let target = comment_line
+                .checked_sub(up)
+                .filter(|&target| target > 0)
+                .ok_or(AnnotationError::OutOfRange { line: comment_line })?;
+            (target, &rest[up..])
+        } else {
+            let down = rest.len() - rest.trim_start_matches('v').len();
+            if down > 0 && rest[down..].starts_with([' ', '\t']) {
+                (comment_line + down, &rest[down..])
+            } else {
+                (comment_line, rest)
+            }
+        };
+        let rest = rest.trim();
+        let (level, message) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if level.is_empty() {
+            return Err(AnnotationError::MissingLevel { line: comment_line });
+        }
+        annotations.push(Annotation {
+            diagnostic: Diagnostic::new(target, level, message.trim()),
+            comment_line,
+        });
+    }
+    Ok(annotations)
+}
+
+/// The result of matching diagnostics against annotations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiagnosticsDiff {
+    /// Actual diagnostics without matching annotations.
+    pub unexpected: Vec<Diagnostic>,
+    /// Annotations without matching diagnostics.
+    pub missing: Vec<Annotation>,
+}
+
+impl DiagnosticsDiff {
+    /// Matches each annotation with the first unmatched diagnostic satisfying it.
+    pub fn new(actual: &[Diagnostic], annotations: &[Annotation]) -> Self {
+        let mut matched = vec![false; actual.len()];
+        let mut missing = Vec::new();
+        for annotation in annotations {
+            let found = actual
+                .iter()
+                .enumerate()
+                .position(|(i, actual)| !matched[i] && annotation.diagnostic.is_matched_by(actual));
+            match found {
+                Some(i) => matched[i] = true,
+                None => missing.push(annotation.clone()),
+            }
+        }
+        let mut unexpected = actual
+            .iter()
+            .zip(&matched)
+            .filter(|&(_, &matched)| !matched)
+            .map(|(actual, _)| actual.clone())
+            .collect::<Vec<_>>();
+        unexpected.sort_by_key(|diagnostic| diagnostic.line);
+        missing.sort_by_key(|annotation| annotation.diagnostic.line);
+        Self {
+            unexpected,
+            missing,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unexpected.is_empty() && self.missing.is_empty()
+    }
+}
+
+impl fmt::Display for DiagnosticsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for diagnostic in &self.unexpected {
+            writeln!(f, "  unexpected: {}", diagnostic)?;
+        }
+        for annotation in &self.missing {
+            writeln!(
+                f,
+                "  missing: {} (annotated at line {})",
+                annotation.diagnostic, annotation.comment_line
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Replaces the annotations in the source with the ones describing the diagnostics.
+///
+/// Lines consisting only of annotations are removed.
+/// Each diagnostic is written after the line it refers to, followed by `//~|` lines if there are more.
+///
+/// # Panics
+///
+/// If a diagnostic refers to a line out of the source.
+///
+/// # Example
+///
+/// ```rust
+/// use testdata::{rewrite_annotations, Diagnostic};
+///
+/// let src = "let x = 1; //~ ERROR old\n//~^ WARNING old\nfoo();\n";
+/// let diagnostics = [
+///     Diagnostic::new(1, "WARNING", "unused"),
+///     Diagnostic::new(1, "HELP", "prefix it"),
+/// ];
+/// assert_eq!(
+///     rewrite_annotations(src, "//~", &diagnostics),
+///     "let x = 1; //~ WARNING unused\n//~| HELP prefix it\nfoo();\n",
+/// );
+/// ```
+pub fn rewrite_annotations(src: &str, marker: &str, diagnostics: &[Diagnostic]) -> String {
+    // Lines without annotations, and the new index of each original line
+    let mut lines = Vec::<(&str, &str)>::new();
+    let mut new_index = Vec::new();
+    for line in src.split_inclusive('\n') {
+        let (body, ending) = split_line_ending(line);
+        match body.find(marker) {
+            Some(pos) if body[..pos].trim().is_empty() => {}
+            Some(pos) => lines.push((body[..pos].trim_end(), ending)),
+            None => lines.push((body, ending)),
+        }
+        new_index.push(lines.len().saturating_sub(1));
+    }
+
+    let mut by_line = vec![Vec::new(); lines.len()];
+    for diagnostic in diagnostics {
+        let index = diagnostic
+            .line
+            .checked_sub(1)
+            .and_then(|i| new_index.get(i))
+            .filter(|_| !lines.is_empty())
+            .unwrap_or_else(|| panic!("Diagnostic out of the source: {}", diagnostic));
+        by_line[*index].push(diagnostic);
+    }
+
+    let mut result = String::with_capacity(src.len());
+    for ((body, ending), diagnostics) in lines.into_iter().zip(by_line) {
+        result.push_str(body);
+        let indent = &body[..body.len() - body.trim_start().len()];
+        for (i, diagnostic) in diagnostics.into_iter().enumerate() {
+            let message = diagnostic.message.lines().next().unwrap_or("");
+            let annotation = format!("{} {}", diagnostic.level, message);
+            if i == 0 {
+                result.push_str(&format!(" {} {}", marker, annotation.trim_end()));
+            } else {
+                result.push_str(if ending.is_empty() { "\n" } else { ending });
+                result.push_str(&format!("{}{}| {}", indent, marker, annotation.trim_end()));
+            }
+        }
+        result.push_str(ending);
+    }
+    result
+}
+
+fn split_line_ending(line: &str) -> (&str, &str) {
+    if let Some(body) = line.strip_suffix("\r\n") {
+        (body, "\r\n")
+    } else if let Some(body) = line.strip_suffix('\n') {
+        (body, "\n")
+    } else {
+        (line, "")
+    }
+}
+
+/// Treats `WARN` and `WARNING` as the same, and ignores the case.
+fn normalize_level(level: &str) -> String {
+    let level = level.to_uppercase();
+    if level == "WARN" {
+        "WARNING".to_owned()
+    } else {
+        level
+    }
+}
+
+/// The implementation of [`assert_diagnostics!`].
+pub fn assert_diagnostics_helper<I>(actual: I, test_file: &TestFile, marker: &str)
+where
+    I: IntoIterator,
+    I::Item: Into<Diagnostic>,
+{
+    let actual = actual.into_iter().map(Into::into).collect::<Vec<_>>();
+    let src = test_file.raw_read();
+    let src = str::from_utf8(&src).unwrap_or_else(|e| panic!("{}: {}", test_file, e));
+    let annotations =
+        parse_annotations(src, marker).unwrap_or_else(|e| panic!("{}: {}", test_file, e));
+    let diff = DiagnosticsDiff::new(&actual, &annotations);
+    if diff.is_empty() {
+        test_file.clear_review();
+        return;
+    }
+    let mode = SnapshotMode::current();
+    if mode == SnapshotMode::All {
+        test_file.raw_write(rewrite_annotations(src, marker, &actual).as_bytes());
+        test_file.clear_review();
+        return;
+    }
+    let note = if mode == SnapshotMode::Review {
+        review_note(
+            mode,
+            test_file,
+            rewrite_annotations(src, marker, &actual).as_bytes(),
+        )
+    } else {
+        String::new()
+    };
+    panic!(
+        "Diagnostics mismatch: {}\n{}{}",
+        test_file,
+        diff.to_string().trim_end(),
+        note
+    );
+}
@@ -0,0 +1,67 @@
+//! Encodings declared by types, for [`Encoded`](crate::Encoded).
+
+use encoding_rs::Encoding;
+
+/// An encoding declared by a type, so that it can be chosen in the type of a test input.
+///
+/// # Example
+///
+/// ```rust
+/// use testdata::encoding_rs::{Encoding, ISO_8859_2};
+/// use testdata::encodings::DeclaredEncoding;
+/// use testdata::{Encoded, TestInput};
+///
+/// struct Latin2;
+///
+/// impl DeclaredEncoding for Latin2 {
+///     fn encoding() -> &'static Encoding {
+///         ISO_8859_2
+///     }
+/// }
+///
+/// let input = Encoded::<Latin2>::read_from(b"\xB3\xF3d\xBC\n");
+/// assert_eq!(input.text, "łódź\n");
+/// ```
+pub trait DeclaredEncoding {
+    /// The encoding assumed in the absence of a byte order mark.
+    fn encoding() -> &'static Encoding;
+}
+
+macro_rules! declared_encodings {
+    ($($(#[$attr:meta])* $name:ident => $encoding:ident,)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            pub struct $name;
+
+            impl DeclaredEncoding for $name {
+                fn encoding() -> &'static Encoding {
+                    encoding_rs::$encoding
+                }
+            }
+        )*
+    };
+}
+
+declared_encodings! {
+    /// UTF-8.
+    Utf8 => UTF_8,
+    /// UTF-16 in little endian.
+    Utf16Le => UTF_16LE,
+    /// UTF-16 in big endian.
+    Utf16Be => UTF_16BE,
+    /// Windows-1252, also used for ISO-8859-1.
+    Windows1252 => WINDOWS_1252,
+    /// Shift_JIS.
+    ShiftJis => SHIFT_JIS,
+    /// EUC-JP.
+    EucJp => EUC_JP,
+    /// EUC-KR.
+    EucKr => EUC_KR,
+    /// GBK.
+    Gbk => GBK,
+    /// GB18030.
+    Gb18030 => GB18030,
+    /// Big5.
+    Big5 => BIG5,
+}
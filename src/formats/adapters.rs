@@ -0,0 +1,254 @@
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use std::str::{self, FromStr, Utf8Error};
+
+use crate::input_error::Position;
+use crate::test_input::TestInput;
+
+/// An error from the `FromStr`-based adapters, with the location of the failing part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError<E> {
+    /// Byte offset of the failing part.
+    pub offset: usize,
+    pub kind: ParseErrorKind<E>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind<E> {
+    /// The input is not a valid UTF-8 string.
+    Utf8(Utf8Error),
+    /// `FromStr` failed for a part of the input.
+    Parse(E),
+    /// The input has a wrong number of tokens.
+    TokenCount { expected: usize, found: usize },
+}
+
+impl<E> ParseError<E> {
+    fn utf8(e: Utf8Error) -> Self {
+        Self {
+            offset: e.valid_up_to(),
+            kind: ParseErrorKind::Utf8(e),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::Utf8(e) => write!(f, "{}", e),
+            ParseErrorKind::Parse(e) => write!(f, "{}", e),
+            ParseErrorKind::TokenCount { expected, found } => {
+                write!(f, "Expected {} tokens, found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> Error for ParseError<E> {}
+
+/// Parses the whole input, with surrounding ASCII whitespace removed, via `FromStr`.
+///
+/// ```rust
+/// use testdata::{Parsed, TestInput};
+///
+/// let Parsed(n) = Parsed::<u32>::read_from(b"42\n");
+/// assert_eq!(n, 42);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Parsed<T>(pub T);
+
+impl<T> TestInput for Parsed<T>
+where
+    T: FromStr,
+    T::Err: fmt::Debug + fmt::Display,
+{
+    type Err = ParseError<T::Err>;
+
+    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+        let s = str::from_utf8(data).map_err(ParseError::utf8)?;
+        let (offset, token) = trim_with_offset(s, 0);
+        let value = parse_at(token, offset)?;
+        Ok(Parsed(value))
+    }
+
+    fn error_position(_data: &[u8], err: &Self::Err) -> Option<Position> {
+        Some(Position::Offset(err.offset))
+    }
+}
+
+/// Parses each non-empty line, with surrounding ASCII whitespace removed, via `FromStr`.
+///
+/// ```rust
+/// use testdata::{Lines, TestInput};
+///
+/// let Lines(lines) = Lines::<f64>::read_from(b"1.5\n\n2.5\n");
+/// assert_eq!(lines, vec![1.5, 2.5]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Lines<T>(pub Vec<T>);
+
+impl<T> TestInput for Lines<T>
+where
+    T: FromStr,
+    T::Err: fmt::Debug + fmt::Display,
+{
+    type Err = ParseError<T::Err>;
+
+    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+        let s = str::from_utf8(data).map_err(ParseError::utf8)?;
+        let mut values = Vec::new();
+        let mut line_start = 0;
+        for line in s.split_inclusive('\n') {
+            let (offset, token) = trim_with_offset(line, line_start);
+            line_start += line.len();
+            if !token.is_empty() {
+                values.push(parse_at(token, offset)?);
+            }
+        }
+        Ok(Lines(values))
+    }
+
+    fn error_position(_data: &[u8], err: &Self::Err) -> Option<Position> {
+        Some(Position::Offset(err.offset))
+    }
+}
+
+/// Parses each token separated by ASCII whitespace via `FromStr`.
+///
+/// Tuples of up to four `FromStr` types also implement [`TestInput`]
+/// in the same way, requiring the exact number of tokens.
+/// Their errors keep the error of the failing token, boxed.
+///
+/// ```rust
+/// use testdata::{TestInput, Tokens};
+///
+/// let Tokens(tokens) = Tokens::<i32>::read_from(b"1 2\n-3\n");
+/// assert_eq!(tokens, vec![1, 2, -3]);
+///
+/// let (name, n) = <(String, u32)>::read_from(b"foo 42\n");
+/// assert_eq!((&name[..], n), ("foo", 42));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Tokens<T>(pub Vec<T>);
+
+impl<T> TestInput for Tokens<T>
+where
+    T: FromStr,
+    T::Err: fmt::Debug + fmt::Display,
+{
+    type Err = ParseError<T::Err>;
+
+    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+        let s = str::from_utf8(data).map_err(ParseError::utf8)?;
+        let values = tokens(s)
+            .map(|(offset, token)| parse_at(token, offset))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Tokens(values))
+    }
+
+    fn error_position(_data: &[u8], err: &Self::Err) -> Option<Position> {
+        Some(Position::Offset(err.offset))
+    }
+}
+
+macro_rules! impl_tuple {
+    ($len:expr; $($t:ident),*) => {
+        impl<$($t),*> TestInput for ($($t,)*)
+        where
+            $(
+                $t: FromStr,
+                $t::Err: Error + Send + Sync + 'static,
+            )*
+        {
+            type Err = ParseError<Box<dyn Error + Send + Sync>>;
+
+            #[allow(non_snake_case)]
+            fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+                let s = str::from_utf8(data).map_err(ParseError::utf8)?;
+                let tokens = tokens(s).collect::<Vec<_>>();
+                if tokens.len() != $len {
+                    return Err(ParseError {
+                        offset: tokens.get($len).map_or(s.len(), |&(offset, _)| offset),
+                        kind: ParseErrorKind::TokenCount {
+                            expected: $len,
+                            found: tokens.len(),
+                        },
+                    });
+                }
+                let mut tokens = tokens.into_iter();
+                $(
+                    let (offset, token) = tokens.next().unwrap();
+                    let $t = token.parse::<$t>().map_err(|e| ParseError {
+                        offset,
+                        kind: ParseErrorKind::Parse(e.into()),
+                    })?;
+                )*
+                Ok(($($t,)*))
+            }
+
+            fn error_position(_data: &[u8], err: &Self::Err) -> Option<Position> {
+                Some(Position::Offset(err.offset))
+            }
+        }
+    };
+}
+
+impl_tuple!(1; A);
+impl_tuple!(2; A, B);
+impl_tuple!(3; A, B, C);
+impl_tuple!(4; A, B, C, D);
+
+/// Reads the input with surrounding ASCII whitespace removed, like the other adapters.
+///
+/// ```rust
+/// use testdata::{TestInput, Trimmed};
+///
+/// let Trimmed(s) = Trimmed::<String>::read_from(b"\n  foo bar \n");
+/// assert_eq!(s, "foo bar");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Trimmed<T>(pub T);
+
+impl<T> TestInput for Trimmed<T>
+where
+    T: TestInput,
+{
+    type Err = T::Err;
+
+    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+        let value = T::try_read_from(data.trim_ascii())?;
+        Ok(Trimmed(value))
+    }
+
+    fn try_read_from_path(data: &[u8], path: &Path) -> Result<Self, Self::Err> {
+        let value = T::try_read_from_path(data.trim_ascii(), path)?;
+        Ok(Trimmed(value))
+    }
+
+    fn error_position(data: &[u8], err: &Self::Err) -> Option<Position> {
+        let leading = data.len() - data.trim_ascii_start().len();
+        let position = T::error_position(data.trim_ascii(), err)?;
+        Some(position.offset_by(data, leading))
+    }
+}
+
+/// Trims the string and returns it with its offset in the input.
+fn trim_with_offset(s: &str, base: usize) -> (usize, &str) {
+    let trimmed = s.trim_ascii_start();
+    let offset = base + s.len() - trimmed.len();
+    (offset, trimmed.trim_ascii_end())
+}
+
+/// Splits the string by whitespace, along with the offsets.
+fn tokens(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    s.split_ascii_whitespace()
+        .map(move |token| (token.as_ptr() as usize - s.as_ptr() as usize, token))
+}
+
+fn parse_at<T: FromStr>(token: &str, offset: usize) -> Result<T, ParseError<T::Err>> {
+    token.parse().map_err(|e| ParseError {
+        offset,
+        kind: ParseErrorKind::Parse(e),
+    })
+}
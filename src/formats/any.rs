@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+#[cfg(feature = "json")]
+use crate::formats::json::JsonFormat;
+#[cfg(feature = "ron")]
+use crate::formats::ron::RonFormat;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml", feature = "ron"))]
+use crate::formats::serialized::Format;
+#[cfg(feature = "toml")]
+use crate::formats::toml::TomlFormat;
+#[cfg(feature = "yaml")]
+use crate::formats::yaml::YamlFormat;
+use crate::input_error::Position;
+use crate::test_input::TestInput;
+
+/// Represents an error in reading [`AnyFormat`].
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "serde")))]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum AnyFormatError {
+    #[error("AnyFormat needs the file path to choose the format")]
+    MissingPath,
+    #[error("Unsupported extension for AnyFormat: {}", .0.display())]
+    UnknownExtension(PathBuf),
+    #[cfg(feature = "json")]
+    #[error(transparent)]
+    Json(serde_json::Error),
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    Toml(toml::de::Error),
+    #[cfg(feature = "yaml")]
+    #[error(transparent)]
+    Yaml(serde_norway::Error),
+    #[cfg(feature = "ron")]
+    #[error(transparent)]
+    Ron(ron::error::SpannedError),
+}
+
+/// A value read from JSON, TOML, YAML or RON, depending on the file extension.
+///
+/// The extensions are `.json`, `.toml`, `.yaml` or `.yml`, and `.ron`,
+/// each of which requires the respective feature.
+/// As the format is only known from the path, this can only be read from files,
+/// not from [`Sections`](crate::Sections) or embedded data.
+///
+/// # Example
+///
+/// Combined with a glob listing each extension, one test covers every syntax.
+/// If the same stem exists with several extensions, the first pattern wins.
+///
+/// ```rust,ignore
+/// #[testdata::files]
+/// #[test]
+/// fn test_config(
+///     #[glob = "tests/config/*.json,tests/config/*.toml,tests/config/*.yaml"]
+///     config: AnyFormat<Config>,
+/// ) {
+///     assert!(config.0.validate().is_ok());
+/// }
+/// ```
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "serde")))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct AnyFormat<T>(pub T);
+
+impl<T> TestInput for AnyFormat<T>
+where
+    T: DeserializeOwned,
+{
+    type Err = AnyFormatError;
+
+    fn try_read_from(_data: &[u8]) -> Result<Self, Self::Err> {
+        Err(AnyFormatError::MissingPath)
+    }
+
+    fn try_read_from_path(data: &[u8], path: &Path) -> Result<Self, Self::Err> {
+        let _ = data;
+        let result = match path.extension().and_then(|ext| ext.to_str()) {
+            #[cfg(feature = "json")]
+            Some("json") => JsonFormat::decode(data).map_err(AnyFormatError::Json),
+            #[cfg(feature = "toml")]
+            Some("toml") => TomlFormat::decode(data).map_err(AnyFormatError::Toml),
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => YamlFormat::decode(data).map_err(AnyFormatError::Yaml),
+            #[cfg(feature = "ron")]
+            Some("ron") => RonFormat::decode(data).map_err(AnyFormatError::Ron),
+            _ => Err(AnyFormatError::UnknownExtension(path.to_owned())),
+        };
+        result.map(AnyFormat)
+    }
+
+    fn error_position(data: &[u8], err: &Self::Err) -> Option<Position> {
+        let _ = data;
+        match err {
+            AnyFormatError::MissingPath | AnyFormatError::UnknownExtension(_) => None,
+            #[cfg(feature = "json")]
+            AnyFormatError::Json(err) => JsonFormat::error_position(data, err),
+            #[cfg(feature = "toml")]
+            AnyFormatError::Toml(err) => TomlFormat::error_position(data, err),
+            #[cfg(feature = "yaml")]
+            AnyFormatError::Yaml(err) => YamlFormat::error_position(data, err),
+            #[cfg(feature = "ron")]
+            AnyFormatError::Ron(err) => RonFormat::error_position(data, err),
+        }
+    }
+}
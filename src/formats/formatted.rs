@@ -0,0 +1,88 @@
+use std::fmt;
+use std::str::{self, Utf8Error};
+
+use crate::snapshots::Snapshot;
+use crate::test_input::TestInput;
+
+macro_rules! formatted_snapshot {
+    ($(#[$attr:meta])* $name:ident, $bound:ident, $format:literal) => {
+        $(#[$attr])*
+        #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new<T>(value: &T) -> Self
+            where
+                T: fmt::$bound + ?Sized,
+            {
+                let mut text = format!($format, value);
+                if !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                Self(text)
+            }
+
+            /// Returns the formatted text.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        /// Prints the text as is, so that a failed assertion shows a line-wise diff.
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl TestInput for $name {
+            type Err = Utf8Error;
+
+            fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+                let text = str::from_utf8(data)?;
+                Ok(Self(text.to_owned()))
+            }
+        }
+
+        impl Snapshot for $name {
+            type Borrowed = $name;
+            type Owned = $name;
+
+            fn borrow(&self) -> &Self::Borrowed {
+                self
+            }
+
+            fn to_bytes(&self) -> Vec<u8> {
+                self.0.as_bytes().to_owned()
+            }
+        }
+    };
+}
+
+formatted_snapshot!(
+    /// A snapshot of the `{:#?}` representation of a value.
+    ///
+    /// The value itself need not be readable from the file.
+    /// See also [`assert_debug_snapshot!`](crate::assert_debug_snapshot).
+    ///
+    /// ```rust,ignore
+    /// assert_snapshot!(DebugSnapshot::new(&ast), snapshot = output);
+    /// ```
+    DebugSnapshot,
+    Debug,
+    "{:#?}"
+);
+
+formatted_snapshot!(
+    /// A snapshot of the `Display` representation of a value.
+    ///
+    /// The value itself need not be readable from the file.
+    /// See also [`assert_display_snapshot!`](crate::assert_display_snapshot).
+    ///
+    /// ```rust,ignore
+    /// assert_snapshot!(DisplaySnapshot::new(&error), snapshot = output);
+    /// ```
+    DisplaySnapshot,
+    Display,
+    "{}"
+);
@@ -0,0 +1,143 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::input_error::Position;
+use crate::test_input::TestInput;
+
+/// Represents an error in reading a file with front matter.
+#[derive(Debug)]
+pub enum FrontMatterError<M, B> {
+    /// The file does not start with a front matter fence.
+    MissingHeader,
+    /// The front matter is not closed. The offset is where it starts.
+    Unterminated { offset: usize },
+    /// The front matter cannot be read. The offset is where its contents start.
+    Meta { offset: usize, error: M },
+    /// The body cannot be read. The offset is where the body starts.
+    Body { offset: usize, error: B },
+}
+
+impl<M: fmt::Display, B: fmt::Display> fmt::Display for FrontMatterError<M, B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrontMatterError::MissingHeader => {
+                f.write_str("Expected front matter starting with `---`, `+++` or `/*---`")
+            }
+            FrontMatterError::Unterminated { .. } => f.write_str("Unterminated front matter"),
+            FrontMatterError::Meta { error, .. } => {
+                write!(f, "Error in the front matter: {}", error)
+            }
+            FrontMatterError::Body { error, .. } => write!(f, "Error in the body: {}", error),
+        }
+    }
+}
+
+impl<M: Error, B: Error> Error for FrontMatterError<M, B> {}
+
+/// A file with a metadata header (front matter) followed by a body.
+///
+/// The header is read as `M`, typically one of the serde-based formats like
+/// `Yaml<T>`, `Toml<T>` or [`Json<T>`](crate::Json), and the rest as `B`.
+/// The following fences are recognized:
+///
+/// - `---` on the first line up to the next `---` line, like YAML front matter.
+/// - `+++` on the first line up to the next `+++` line, like TOML front matter.
+/// - `/*---` on any line up to the next `---*/` line, like test262 metadata.
+///   As the header is a comment, the body is the whole file in this case.
+///
+/// Positions in parse errors refer to the whole file.
+///
+/// # Example
+///
+/// ```rust
+/// use testdata::{FrontMatter, TestInput, Trimmed};
+///
+/// let case = FrontMatter::<Trimmed<String>, String>::read_from(b"---\nflags: [strict]\n---\nfoo();\n");
+/// assert_eq!(case.meta.0, "flags: [strict]");
+/// assert_eq!(case.body, "foo();\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct FrontMatter<M, B> {
+    pub meta: M,
+    pub body: B,
+}
+
+impl<M, B> TestInput for FrontMatter<M, B>
+where
+    M: TestInput,
+    B: TestInput,
+{
+    type Err = FrontMatterError<M::Err, B::Err>;
+
+    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+        let split = split(data)?;
+        let meta = M::try_read_from(&data[split.meta_start..split.meta_end]).map_err(|error| {
+            FrontMatterError::Meta {
+                offset: split.meta_start,
+                error,
+            }
+        })?;
+        let body = B::try_read_from(&data[split.body_start..]).map_err(|error| {
+            FrontMatterError::Body {
+                offset: split.body_start,
+                error,
+            }
+        })?;
+        Ok(FrontMatter { meta, body })
+    }
+
+    fn error_position(data: &[u8], err: &Self::Err) -> Option<Position> {
+        match err {
+            FrontMatterError::MissingHeader => Some(Position::Offset(0)),
+            FrontMatterError::Unterminated { offset } => Some(Position::Offset(*offset)),
+            FrontMatterError::Meta { offset, error } => {
+                let split = split::<M::Err, B::Err>(data).ok()?;
+                let meta = &data[split.meta_start..split.meta_end];
+                Some(M::error_position(meta, error)?.offset_by(data, *offset))
+            }
+            FrontMatterError::Body { offset, error } => {
+                Some(B::error_position(&data[*offset..], error)?.offset_by(data, *offset))
+            }
+        }
+    }
+}
+
+struct Split {
+    meta_start: usize,
+    meta_end: usize,
+    body_start: usize,
+}
+
+/// Finds the header and the body.
+fn split<M, B>(data: &[u8]) -> Result<Split, FrontMatterError<M, B>> {
+    let mut lines = Vec::new();
+    let mut pos = 0;
+    for line in data.split_inclusive(|&b| b == b'\n') {
+        lines.push((pos, pos + line.len(), line.trim_ascii_end()));
+        pos += line.len();
+    }
+
+    let (open, close, body_is_whole) = match lines.first() {
+        Some((_, _, b"---")) => (0, &b"---"[..], false),
+        Some((_, _, b"+++")) => (0, &b"+++"[..], false),
+        _ => {
+            let open = lines
+                .iter()
+                .position(|&(_, _, line)| line == b"/*---")
+                .ok_or(FrontMatterError::MissingHeader)?;
+            (open, &b"---*/"[..], true)
+        }
+    };
+    let meta_start = lines[open].1;
+    let close = lines[open + 1..]
+        .iter()
+        .find(|&&(_, _, line)| line == close)
+        .ok_or(FrontMatterError::Unterminated {
+            offset: lines[open].0,
+        })?;
+    Ok(Split {
+        meta_start,
+        meta_end: close.0,
+        body_start: if body_is_whole { 0 } else { close.1 },
+    })
+}
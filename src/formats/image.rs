@@ -0,0 +1,252 @@
+use std::fmt;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use png::{BitDepth, ColorType, Decoder, Encoder, Transformations};
+use thiserror::Error;
+
+use crate::snapshots::Snapshot;
+use crate::test_files::TestFile;
+use crate::test_input::TestInput;
+
+/// Represents an error in decoding a PNG image.
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "image")))]
+#[derive(Debug, Error)]
+#[error("Cannot decode the PNG image: {0}")]
+pub struct ImageDecodeError(#[from] png::DecodingError);
+
+/// An RGBA image, stored as PNG and compared pixel by pixel.
+///
+/// Two images are considered equal if they have the same size and at most
+/// `max_diff_ratio` of the pixels differ by more than `tolerance` in some channel.
+/// Both default to zero, that is, an exact match.
+///
+/// On mismatch, `assert_snapshot!` writes `<name>-actual.png` and `<name>-diff.png`
+/// next to the snapshot, and removes them once it matches.
+/// In the diff, differing pixels are red and the rest is dimmed.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let image = Image::new(width, height, render(&scene)).tolerance(2).max_diff_ratio(0.001);
+/// assert_snapshot!(image, snapshot = output);
+/// ```
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "image")))]
+#[derive(Clone)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// Pixels in RGBA order, row by row.
+    pub pixels: Vec<u8>,
+    tolerance: u8,
+    max_diff_ratio: f64,
+}
+
+impl Image {
+    /// Creates an image from RGBA pixels.
+    ///
+    /// # Panics
+    ///
+    /// If the length of `pixels` does not match the size.
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width as usize * height as usize * 4,
+            "The pixels do not match the size {}x{}",
+            width,
+            height
+        );
+        Self {
+            width,
+            height,
+            pixels,
+            tolerance: 0,
+            max_diff_ratio: 0.0,
+        }
+    }
+
+    /// Sets the allowed difference in each channel.
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the allowed ratio of pixels exceeding the tolerance, between 0 and 1.
+    pub fn max_diff_ratio(mut self, max_diff_ratio: f64) -> Self {
+        self.max_diff_ratio = max_diff_ratio;
+        self
+    }
+
+    /// Decodes a PNG image, converting it to 8-bit RGBA.
+    pub fn decode_png(data: &[u8]) -> Result<Self, ImageDecodeError> {
+        let mut decoder = Decoder::new(Cursor::new(data));
+        decoder.set_transformations(Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf)?;
+        buf.truncate(info.buffer_size());
+        let pixels = match info.color_type {
+            ColorType::Rgba => buf,
+            ColorType::Rgb => buf
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            ColorType::GrayscaleAlpha => buf
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            ColorType::Grayscale => buf.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            // Expanded by the transformations
+            ColorType::Indexed => unreachable!(),
+        };
+        Ok(Self::new(info.width, info.height, pixels))
+    }
+
+    /// Encodes the image as PNG.
+    pub fn encode_png(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut encoder = Encoder::new(&mut data, self.width, self.height);
+        encoder.set_color(ColorType::Rgba);
+        encoder.set_depth(BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&self.pixels).unwrap();
+        writer.finish().unwrap();
+        data
+    }
+
+    /// Compares the pixels, returning the diff image and the number of differing pixels.
+    fn diff(&self, other: &Image) -> Option<(Image, usize)> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return None;
+        }
+        let tolerance = self.tolerance.max(other.tolerance);
+        let mut count = 0;
+        let pixels = self
+            .pixels
+            .chunks_exact(4)
+            .zip(other.pixels.chunks_exact(4))
+            .flat_map(|(p, q)| {
+                let differs = p.iter().zip(q).any(|(a, b)| a.abs_diff(*b) > tolerance);
+                if differs {
+                    count += 1;
+                    [255, 0, 0, 255]
+                } else {
+                    let luma = (p[0] as u32 * 3 + p[1] as u32 * 6 + p[2] as u32) / 10;
+                    let dimmed = (128 + luma / 2) as u8;
+                    [dimmed, dimmed, dimmed, 255]
+                }
+            })
+            .collect();
+        Some((Image::new(self.width, self.height, pixels), count))
+    }
+
+    fn max_diff_count(&self, other: &Image) -> usize {
+        let ratio = self.max_diff_ratio.max(other.max_diff_ratio);
+        ((self.pixels.len() / 4) as f64 * ratio) as usize
+    }
+}
+
+impl PartialEq for Image {
+    fn eq(&self, other: &Self) -> bool {
+        match self.diff(other) {
+            Some((_, count)) => count <= self.max_diff_count(other),
+            None => false,
+        }
+    }
+}
+
+impl fmt::Debug for Image {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Image")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TestInput for Image {
+    type Err = ImageDecodeError;
+
+    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+        Self::decode_png(data)
+    }
+}
+
+impl Snapshot for Image {
+    type Borrowed = Image;
+    type Owned = Image;
+
+    fn borrow(&self) -> &Self::Borrowed {
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.encode_png()
+    }
+
+    fn mismatch_message(
+        actual: &Self::Borrowed,
+        expected: &Self::Borrowed,
+        test_file: &TestFile,
+    ) -> Option<String> {
+        let snapshot_path = test_file.path_for_writing();
+        let mut message = match actual.diff(expected) {
+            Some((_, count)) => format!(
+                "{} of {} pixels differ by more than {} (at most {} allowed)\n\
+                diff: {}\n",
+                count,
+                actual.pixels.len() / 4,
+                actual.tolerance.max(expected.tolerance),
+                actual.max_diff_count(expected),
+                sibling_path(snapshot_path, "diff").display()
+            ),
+            None => format!(
+                "expected {}x{} image, got {}x{}\n",
+                expected.width, expected.height, actual.width, actual.height
+            ),
+        };
+        message.push_str(&format!(
+            "actual: {}",
+            sibling_path(snapshot_path, "actual").display()
+        ));
+        Some(message)
+    }
+
+    /// Writes `foo-out-actual.png` and `foo-out-diff.png` next to the snapshot,
+    /// and removes them once the snapshot matches.
+    ///
+    /// Failures are ignored so as not to hide the mismatch itself.
+    fn write_artifacts(
+        actual: &Self::Borrowed,
+        expected: Option<&Self::Borrowed>,
+        test_file: &TestFile,
+    ) {
+        let snapshot_path = test_file.path_for_writing();
+        let actual_path = sibling_path(snapshot_path, "actual");
+        let diff_path = sibling_path(snapshot_path, "diff");
+        let diff = expected.and_then(|expected| actual.diff(expected));
+        if expected.is_some() {
+            if let Some(parent) = actual_path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let _ = fs::write(&actual_path, actual.encode_png());
+        } else {
+            let _ = fs::remove_file(&actual_path);
+        }
+        match diff {
+            Some((diff, _)) => {
+                let _ = fs::write(&diff_path, diff.encode_png());
+            }
+            None => {
+                let _ = fs::remove_file(&diff_path);
+            }
+        }
+    }
+}
+
+/// Turns `foo-out.png` into `foo-out-<suffix>.png`.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}-{}.png", stem, suffix))
+}
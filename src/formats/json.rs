@@ -1,39 +1,90 @@
-use bytemuck::TransparentWrapper;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use serde_json::{Map, Value};
 
-use crate::snapshots::Snapshot;
-use crate::test_input::TestInput;
+use crate::formats::serialized::{serialized_newtype, Format};
+use crate::input_error::Position;
 
-#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "serde_json")))]
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, TransparentWrapper)]
-#[repr(transparent)]
-pub struct Json<T>(pub T);
+serialized_newtype!(
+    /// A JSON document, read and written through serde.
+    ///
+    /// The snapshot is pretty-printed with a trailing newline, in the order given by
+    /// the `Serialize` impl. Use [`SortedJson`] to sort the object keys.
+    ///
+    /// Snapshots are compared after parsing, so whitespace-only differences do not matter.
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "json")))]
+    Json,
+    JsonFormat
+);
 
-impl<T> TestInput for Json<T>
-where
-    T: DeserializeOwned,
-{
-    type Err = serde_json::Error;
+serialized_newtype!(
+    /// Same as [`Json`], but the snapshot has the object keys sorted.
+    ///
+    /// This makes snapshots of `HashMap`s deterministic.
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "json")))]
+    SortedJson,
+    SortedJsonFormat
+);
 
-    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
-        let value = serde_json::from_slice(data)?;
-        Ok(Json(value))
+/// The format of [`Json`], for use with [`Serialized`](crate::Serialized).
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "json")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct JsonFormat;
+
+impl Format for JsonFormat {
+    type Error = serde_json::Error;
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Self::Error> {
+        serde_json::from_slice(data)
+    }
+
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+        let mut bytes = serde_json::to_vec_pretty(value).unwrap();
+        bytes.push(b'\n');
+        bytes
+    }
+
+    fn error_position(_data: &[u8], err: &Self::Error) -> Option<Position> {
+        (err.line() > 0).then(|| Position::LineColumn {
+            line: err.line(),
+            column: err.column(),
+        })
     }
 }
 
-impl<T> Snapshot for Json<T>
-where
-    T: DeserializeOwned + Serialize,
-{
-    type Borrowed = Json<T>;
-    type Owned = Json<T>;
+/// The format of [`SortedJson`], for use with [`Serialized`](crate::Serialized).
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "json")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SortedJsonFormat;
 
-    fn borrow(&self) -> &Self::Borrowed {
-        &self
+impl Format for SortedJsonFormat {
+    type Error = serde_json::Error;
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Self::Error> {
+        JsonFormat::decode(data)
+    }
+
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+        JsonFormat::encode(&sort_keys(serde_json::to_value(value).unwrap()))
     }
 
-    fn to_bytes(&self) -> Vec<u8> {
-        serde_json::to_vec(&self.0).unwrap()
+    fn error_position(data: &[u8], err: &Self::Error) -> Option<Position> {
+        JsonFormat::error_position(data, err)
+    }
+}
+
+/// Sorts the object keys, regardless of whether `serde_json` preserves the order.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries = object
+                .into_iter()
+                .map(|(k, v)| (k, sort_keys(v)))
+                .collect::<Vec<_>>();
+            entries.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+            Value::Object(entries.into_iter().collect::<Map<_, _>>())
+        }
+        Value::Array(array) => Value::Array(array.into_iter().map(sort_keys).collect()),
+        value => value,
     }
 }
@@ -1,2 +1,21 @@
-#[cfg(any(feature = "serde_json", all(feature = "__doc_cfg", doc)))]
+pub(crate) mod adapters;
+#[cfg(any(feature = "serde", all(feature = "__doc_cfg", doc)))]
+pub(crate) mod any;
+pub(crate) mod formatted;
+pub(crate) mod front_matter;
+#[cfg(any(feature = "image", all(feature = "__doc_cfg", doc)))]
+pub(crate) mod image;
+#[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
 pub(crate) mod json;
+#[cfg(any(feature = "ron", all(feature = "__doc_cfg", doc)))]
+pub(crate) mod ron;
+#[cfg(any(feature = "ron", all(feature = "__doc_cfg", doc)))]
+pub(crate) mod serde_value;
+#[cfg(any(feature = "serde", all(feature = "__doc_cfg", doc)))]
+pub(crate) mod serialized;
+#[cfg(any(feature = "encoding", all(feature = "__doc_cfg", doc)))]
+pub(crate) mod text;
+#[cfg(any(feature = "toml", all(feature = "__doc_cfg", doc)))]
+pub(crate) mod toml;
+#[cfg(any(feature = "yaml", all(feature = "__doc_cfg", doc)))]
+pub(crate) mod yaml;
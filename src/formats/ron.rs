@@ -0,0 +1,59 @@
+use ron::error::SpannedError;
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::formats::serde_value::Value;
+use crate::formats::serialized::{serialized_newtype, Format};
+use crate::input_error::Position;
+#[cfg(feature = "json")]
+use crate::redactions::{pointers, Redaction};
+
+serialized_newtype!(
+    /// A RON document, read and written through serde.
+    ///
+    /// The snapshot is pretty-printed with the keys of the maps sorted,
+    /// and the fields of the structs in the order given by the `Serialize` impl.
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "ron")))]
+    Ron,
+    RonFormat
+);
+
+/// The format of [`Ron`], for use with [`Serialized`](crate::Serialized).
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "ron")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RonFormat;
+
+impl Format for RonFormat {
+    type Error = SpannedError;
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Self::Error> {
+        ron::de::from_bytes(data)
+    }
+
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+        // Capturing the value first allows sorting the maps, keeping the names of the types
+        let mut value = Value::capture(value).unwrap();
+        value.sort_maps();
+        let mut s = ron::ser::to_string_pretty(&value, PrettyConfig::default()).unwrap();
+        s.push('\n');
+        s.into_bytes()
+    }
+
+    fn error_position(_data: &[u8], err: &Self::Error) -> Option<Position> {
+        Some(Position::LineColumn {
+            line: err.position.line,
+            column: err.position.col,
+        })
+    }
+
+    #[cfg(feature = "json")]
+    fn redact<T: Serialize + ?Sized>(value: &T, redactions: &[Redaction]) -> Option<Vec<u8>> {
+        // Redacting the captured value keeps the names of the types, unlike going through JSON
+        let mut value = Value::capture(value).unwrap();
+        for (tokens, replacement) in pointers(redactions) {
+            value.replace_pointer(tokens, replacement);
+        }
+        Some(Self::encode(&value))
+    }
+}
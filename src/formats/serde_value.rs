@@ -0,0 +1,627 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::ser::{
+    self, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant,
+};
+use serde::{Serialize, Serializer};
+
+/// A value captured from a `Serialize` impl, keeping everything of the serde data model.
+///
+/// Unlike `serde_json::Value`, it serializes back the same way as the original value,
+/// including the names of the structs and variants, so formats like RON can still write them.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub(crate) enum Value {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    None,
+    Some(Box<Value>),
+    Unit,
+    UnitStruct(&'static str),
+    UnitVariant(Variant),
+    NewtypeStruct(&'static str, Box<Value>),
+    NewtypeVariant(Variant, Box<Value>),
+    Seq(Vec<Value>),
+    Tuple(Vec<Value>),
+    TupleStruct(&'static str, Vec<Value>),
+    TupleVariant(Variant, Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    Struct(&'static str, Vec<(&'static str, Value)>),
+    StructVariant(Variant, Vec<(&'static str, Value)>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub(crate) struct Variant {
+    name: &'static str,
+    index: u32,
+    variant: &'static str,
+}
+
+impl Value {
+    /// Captures the value, failing as the `Serialize` impl does.
+    pub(crate) fn capture<T: Serialize + ?Sized>(value: &T) -> Result<Value, Error> {
+        value.serialize(Capture)
+    }
+
+    /// Sorts the entries of the maps by key, recursively.
+    pub(crate) fn sort_maps(&mut self) {
+        match self {
+            Value::Map(entries) => {
+                for (key, value) in entries.iter_mut() {
+                    key.sort_maps();
+                    value.sort_maps();
+                }
+                entries.sort_by(|(k1, _), (k2, _)| k1.partial_cmp(k2).unwrap_or(Ordering::Equal));
+            }
+            Value::Some(value)
+            | Value::NewtypeStruct(_, value)
+            | Value::NewtypeVariant(_, value) => value.sort_maps(),
+            Value::Seq(items)
+            | Value::Tuple(items)
+            | Value::TupleStruct(_, items)
+            | Value::TupleVariant(_, items) => items.iter_mut().for_each(Value::sort_maps),
+            Value::Struct(_, fields) | Value::StructVariant(_, fields) => {
+                fields.iter_mut().for_each(|(_, value)| value.sort_maps())
+            }
+            _ => {}
+        }
+    }
+
+    /// Replaces the value at the JSON pointer, seeing the value as `serde_json` would.
+    ///
+    /// So the variants are objects with one key, while the options and the newtype
+    /// structs are transparent. Only the maps with string keys are looked into.
+    #[cfg(feature = "json")]
+    pub(crate) fn replace_pointer(&mut self, tokens: &[String], replacement: &str) {
+        let (token, rest) = if let Some((token, rest)) = tokens.split_first() {
+            (token, rest)
+        } else {
+            *self = Value::Str(replacement.to_owned());
+            return;
+        };
+        let matches = |name: &str| token == "*" || token == name;
+        match self {
+            Value::Some(value) | Value::NewtypeStruct(_, value) => {
+                value.replace_pointer(tokens, replacement)
+            }
+            Value::Seq(items) | Value::Tuple(items) | Value::TupleStruct(_, items) => {
+                replace_in_items(items, token, rest, replacement)
+            }
+            Value::Map(entries) => {
+                for (key, value) in entries {
+                    if matches!(key, Value::Str(key) if matches(key)) {
+                        value.replace_pointer(rest, replacement);
+                    }
+                }
+            }
+            Value::Struct(_, fields) => replace_in_fields(fields, token, rest, replacement),
+            Value::NewtypeVariant(v, value) if matches(v.variant) => {
+                value.replace_pointer(rest, replacement)
+            }
+            Value::TupleVariant(v, _) | Value::StructVariant(v, _)
+                if matches(v.variant) && rest.is_empty() =>
+            {
+                let value = Box::new(Value::Str(replacement.to_owned()));
+                *self = Value::NewtypeVariant(*v, value);
+            }
+            Value::TupleVariant(v, items) if matches(v.variant) => {
+                replace_in_items(items, &rest[0], &rest[1..], replacement)
+            }
+            Value::StructVariant(v, fields) if matches(v.variant) => {
+                replace_in_fields(fields, &rest[0], &rest[1..], replacement)
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+fn replace_in_items(items: &mut [Value], token: &str, rest: &[String], replacement: &str) {
+    if token == "*" {
+        for item in items {
+            item.replace_pointer(rest, replacement);
+        }
+    } else if let Some(item) = token.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+        item.replace_pointer(rest, replacement);
+    }
+}
+
+#[cfg(feature = "json")]
+fn replace_in_fields(
+    fields: &mut [(&'static str, Value)],
+    token: &str,
+    rest: &[String],
+    replacement: &str,
+) {
+    for (key, value) in fields {
+        if token == "*" || token == *key {
+            value.replace_pointer(rest, replacement);
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Value::Bool(v) => serializer.serialize_bool(v),
+            Value::I8(v) => serializer.serialize_i8(v),
+            Value::I16(v) => serializer.serialize_i16(v),
+            Value::I32(v) => serializer.serialize_i32(v),
+            Value::I64(v) => serializer.serialize_i64(v),
+            Value::I128(v) => serializer.serialize_i128(v),
+            Value::U8(v) => serializer.serialize_u8(v),
+            Value::U16(v) => serializer.serialize_u16(v),
+            Value::U32(v) => serializer.serialize_u32(v),
+            Value::U64(v) => serializer.serialize_u64(v),
+            Value::U128(v) => serializer.serialize_u128(v),
+            Value::F32(v) => serializer.serialize_f32(v),
+            Value::F64(v) => serializer.serialize_f64(v),
+            Value::Char(v) => serializer.serialize_char(v),
+            Value::Str(ref v) => serializer.serialize_str(v),
+            Value::Bytes(ref v) => serializer.serialize_bytes(v),
+            Value::None => serializer.serialize_none(),
+            Value::Some(ref v) => serializer.serialize_some(v),
+            Value::Unit => serializer.serialize_unit(),
+            Value::UnitStruct(name) => serializer.serialize_unit_struct(name),
+            Value::UnitVariant(v) => serializer.serialize_unit_variant(v.name, v.index, v.variant),
+            Value::NewtypeStruct(name, ref value) => {
+                serializer.serialize_newtype_struct(name, value)
+            }
+            Value::NewtypeVariant(v, ref value) => {
+                serializer.serialize_newtype_variant(v.name, v.index, v.variant, value)
+            }
+            Value::Seq(ref items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Value::Tuple(ref items) => {
+                let mut tuple = serializer.serialize_tuple(items.len())?;
+                for item in items {
+                    tuple.serialize_element(item)?;
+                }
+                tuple.end()
+            }
+            Value::TupleStruct(name, ref items) => {
+                let mut tuple = serializer.serialize_tuple_struct(name, items.len())?;
+                for item in items {
+                    tuple.serialize_field(item)?;
+                }
+                tuple.end()
+            }
+            Value::TupleVariant(v, ref items) => {
+                let mut tuple =
+                    serializer.serialize_tuple_variant(v.name, v.index, v.variant, items.len())?;
+                for item in items {
+                    tuple.serialize_field(item)?;
+                }
+                tuple.end()
+            }
+            Value::Map(ref entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Value::Struct(name, ref fields) => {
+                let mut s = serializer.serialize_struct(name, fields.len())?;
+                for (key, value) in fields {
+                    s.serialize_field(key, value)?;
+                }
+                s.end()
+            }
+            Value::StructVariant(v, ref fields) => {
+                let mut s = serializer.serialize_struct_variant(
+                    v.name,
+                    v.index,
+                    v.variant,
+                    fields.len(),
+                )?;
+                for (key, value) in fields {
+                    s.serialize_field(key, value)?;
+                }
+                s.end()
+            }
+        }
+    }
+}
+
+/// The error of a `Serialize` impl while capturing the value.
+#[derive(Debug)]
+pub(crate) struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+struct Capture;
+
+impl Serializer for Capture {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = CaptureSeq;
+    type SerializeTuple = CaptureSeq;
+    type SerializeTupleStruct = CaptureSeq;
+    type SerializeTupleVariant = CaptureSeq;
+    type SerializeMap = CaptureMap;
+    type SerializeStruct = CaptureStruct;
+    type SerializeStructVariant = CaptureStruct;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(Value::I8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(Value::I16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(Value::I32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, Error> {
+        Ok(Value::I128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(Value::U8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(Value::U16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(Value::U32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(Value::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, Error> {
+        Ok(Value::U128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        Ok(Value::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(Value::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::Str(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Bytes(v.to_owned()))
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Error> {
+        Ok(Value::Some(Box::new(value.serialize(Capture)?)))
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Value, Error> {
+        Ok(Value::UnitStruct(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::UnitVariant(Variant {
+            name,
+            index,
+            variant,
+        }))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        Ok(Value::NewtypeStruct(
+            name,
+            Box::new(value.serialize(Capture)?),
+        ))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        let variant = Variant {
+            name,
+            index,
+            variant,
+        };
+        Ok(Value::NewtypeVariant(
+            variant,
+            Box::new(value.serialize(Capture)?),
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<CaptureSeq, Error> {
+        Ok(CaptureSeq::new(SeqKind::Seq, len.unwrap_or(0)))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<CaptureSeq, Error> {
+        Ok(CaptureSeq::new(SeqKind::Tuple, len))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<CaptureSeq, Error> {
+        Ok(CaptureSeq::new(SeqKind::TupleStruct(name), len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<CaptureSeq, Error> {
+        let variant = Variant {
+            name,
+            index,
+            variant,
+        };
+        Ok(CaptureSeq::new(SeqKind::TupleVariant(variant), len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<CaptureMap, Error> {
+        Ok(CaptureMap {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<CaptureStruct, Error> {
+        Ok(CaptureStruct {
+            kind: StructKind::Struct(name),
+            fields: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<CaptureStruct, Error> {
+        let variant = Variant {
+            name,
+            index,
+            variant,
+        };
+        Ok(CaptureStruct {
+            kind: StructKind::StructVariant(variant),
+            fields: Vec::with_capacity(len),
+        })
+    }
+}
+
+enum SeqKind {
+    Seq,
+    Tuple,
+    TupleStruct(&'static str),
+    TupleVariant(Variant),
+}
+
+struct CaptureSeq {
+    kind: SeqKind,
+    items: Vec<Value>,
+}
+
+impl CaptureSeq {
+    fn new(kind: SeqKind, len: usize) -> Self {
+        CaptureSeq {
+            kind,
+            items: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(value.serialize(Capture)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value, Error> {
+        Ok(match self.kind {
+            SeqKind::Seq => Value::Seq(self.items),
+            SeqKind::Tuple => Value::Tuple(self.items),
+            SeqKind::TupleStruct(name) => Value::TupleStruct(name, self.items),
+            SeqKind::TupleVariant(variant) => Value::TupleVariant(variant, self.items),
+        })
+    }
+}
+
+impl SerializeSeq for CaptureSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl SerializeTuple for CaptureSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleStruct for CaptureSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleVariant for CaptureSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+struct CaptureMap {
+    entries: Vec<(Value, Value)>,
+    key: Option<Value>,
+}
+
+impl SerializeMap for CaptureMap {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(Capture)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self.key.take().ok_or_else(|| {
+            <Error as ser::Error>::custom("serialize_value called before serialize_key")
+        })?;
+        self.entries.push((key, value.serialize(Capture)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Map(self.entries))
+    }
+}
+
+enum StructKind {
+    Struct(&'static str),
+    StructVariant(Variant),
+}
+
+struct CaptureStruct {
+    kind: StructKind,
+    fields: Vec<(&'static str, Value)>,
+}
+
+impl CaptureStruct {
+    fn finish(self) -> Result<Value, Error> {
+        Ok(match self.kind {
+            StructKind::Struct(name) => Value::Struct(name, self.fields),
+            StructKind::StructVariant(variant) => Value::StructVariant(variant, self.fields),
+        })
+    }
+}
+
+impl SerializeStruct for CaptureStruct {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.fields.push((key, value.serialize(Capture)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl SerializeStructVariant for CaptureStruct {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.fields.push((key, value.serialize(Capture)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
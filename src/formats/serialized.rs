@@ -0,0 +1,239 @@
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use bytemuck::TransparentWrapper;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use testdata_rt::pretty_assertions::StrComparison;
+
+use crate::input_error::Position;
+#[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
+use crate::redactions::{redact_value, Redaction};
+use crate::snapshots::Snapshot;
+use crate::test_files::TestFile;
+use crate::test_input::TestInput;
+
+/// A serde-based file format, used through [`Serialized`].
+///
+/// The built-in formats are also available as [`JsonFormat`](crate::JsonFormat),
+/// [`TomlFormat`](crate::TomlFormat), [`YamlFormat`](crate::YamlFormat)
+/// and [`RonFormat`](crate::RonFormat), with the respective features.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// struct MyFormat;
+///
+/// impl Format for MyFormat {
+///     type Error = my_format::Error;
+///
+///     fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Self::Error> {
+///         my_format::from_slice(data)
+///     }
+///
+///     fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+///         my_format::to_vec(value).unwrap()
+///     }
+/// }
+///
+/// assert_snapshot!(Serialized::<MyFormat, _>::new(result), snapshot = output);
+/// ```
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "serde")))]
+pub trait Format {
+    type Error: Error;
+
+    /// Reads the value from the data.
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Self::Error>;
+
+    /// Writes the value as a snapshot.
+    ///
+    /// # Panics
+    ///
+    /// If the value cannot be represented in the format.
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8>;
+
+    /// Renders the value for the diff shown on snapshot mismatches.
+    ///
+    /// Defaults to the snapshot itself.
+    fn render<T: Serialize + ?Sized>(value: &T) -> String {
+        String::from_utf8_lossy(&Self::encode(value)).into_owned()
+    }
+
+    /// Locates the error from [`Format::decode`] in the data, if possible.
+    fn error_position(data: &[u8], err: &Self::Error) -> Option<Position> {
+        let _ = (data, err);
+        None
+    }
+
+    /// Writes the value as a snapshot, with the JSON pointer redactions applied.
+    ///
+    /// Defaults to redacting the value converted to JSON. Returns `None` if unsupported.
+    #[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "json")))]
+    fn redact<T: Serialize + ?Sized>(value: &T, redactions: &[Redaction]) -> Option<Vec<u8>> {
+        Some(Self::encode(&redact_value(value, redactions)))
+    }
+}
+
+/// A value read and written in the format `F`.
+///
+/// On snapshot mismatch, the values rendered by [`Format::render`] are diffed line by line.
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "serde")))]
+#[derive(TransparentWrapper)]
+#[repr(transparent)]
+#[transparent(T)]
+pub struct Serialized<F, T>(pub T, PhantomData<fn() -> F>);
+
+impl<F, T> Serialized<F, T> {
+    pub fn new(value: T) -> Self {
+        Serialized(value, PhantomData)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<F, T: fmt::Debug> fmt::Debug for Serialized<F, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Serialized").field(&self.0).finish()
+    }
+}
+
+impl<F, T: Clone> Clone for Serialized<F, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+impl<F, T: PartialEq> PartialEq for Serialized<F, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<F, T: Eq> Eq for Serialized<F, T> {}
+
+impl<F, T: Default> Default for Serialized<F, T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<F, T> TestInput for Serialized<F, T>
+where
+    F: Format,
+    T: DeserializeOwned,
+{
+    type Err = F::Error;
+
+    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+        Ok(Self::new(F::decode(data)?))
+    }
+
+    fn error_position(data: &[u8], err: &Self::Err) -> Option<Position> {
+        F::error_position(data, err)
+    }
+}
+
+impl<F, T> Snapshot for Serialized<F, T>
+where
+    F: Format,
+    T: DeserializeOwned + Serialize,
+{
+    type Borrowed = Serialized<F, T>;
+    type Owned = Serialized<F, T>;
+
+    fn borrow(&self) -> &Self::Borrowed {
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        F::encode(&self.0)
+    }
+
+    #[cfg(feature = "json")]
+    fn redact_pointers(&self, redactions: &[Redaction]) -> Option<Vec<u8>> {
+        F::redact(&self.0, redactions)
+    }
+
+    fn mismatch_message(
+        actual: &Self::Borrowed,
+        expected: &Self::Borrowed,
+        _test_file: &TestFile,
+    ) -> Option<String> {
+        let actual = F::render(&actual.0);
+        let expected = F::render(&expected.0);
+        Some(format!(
+            "Diff < actual / snapshot > :\n{}",
+            StrComparison::new(&actual, &expected)
+        ))
+    }
+}
+
+/// Defines a newtype of a value in the format, which reads and writes
+/// snapshots like [`Serialized`] does.
+macro_rules! serialized_newtype {
+    ($(#[$attr:meta])* $name:ident, $format:ty) => {
+        $(#[$attr])*
+        #[derive(
+            Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, bytemuck::TransparentWrapper,
+        )]
+        #[repr(transparent)]
+        pub struct $name<T>(pub T);
+
+        impl<T> std::borrow::Borrow<crate::Serialized<$format, T>> for $name<T> {
+            fn borrow(&self) -> &crate::Serialized<$format, T> {
+                bytemuck::TransparentWrapper::wrap_ref(&self.0)
+            }
+        }
+
+        impl<T> crate::TestInput for $name<T>
+        where
+            T: serde::de::DeserializeOwned,
+        {
+            type Err = <$format as crate::Format>::Error;
+
+            fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+                let value = <$format as crate::Format>::decode(data)?;
+                Ok($name(value))
+            }
+
+            fn error_position(data: &[u8], err: &Self::Err) -> Option<crate::Position> {
+                <$format as crate::Format>::error_position(data, err)
+            }
+        }
+
+        impl<T> crate::Snapshot for $name<T>
+        where
+            T: serde::de::DeserializeOwned + serde::Serialize,
+        {
+            type Borrowed = crate::Serialized<$format, T>;
+            type Owned = $name<T>;
+
+            fn borrow(&self) -> &Self::Borrowed {
+                std::borrow::Borrow::borrow(self)
+            }
+
+            fn to_bytes(&self) -> Vec<u8> {
+                crate::Snapshot::borrow(self).to_bytes()
+            }
+
+            fn mismatch_message(
+                actual: &Self::Borrowed,
+                expected: &Self::Borrowed,
+                test_file: &crate::TestFile,
+            ) -> Option<String> {
+                <Self::Borrowed as crate::Snapshot>::mismatch_message(actual, expected, test_file)
+            }
+
+            #[cfg(feature = "json")]
+            fn redact_pointers(&self, redactions: &[crate::Redaction]) -> Option<Vec<u8>> {
+                crate::Snapshot::borrow(self).redact_pointers(redactions)
+            }
+        }
+    };
+}
+
+pub(crate) use serialized_newtype;
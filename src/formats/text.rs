@@ -0,0 +1,263 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use thiserror::Error;
+
+use crate::encodings::DeclaredEncoding;
+use crate::snapshots::Snapshot;
+use crate::test_files::TestFile;
+use crate::test_input::TestInput;
+
+/// Represents a decoding error in [`Text`].
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "encoding")))]
+#[derive(Debug, Clone, Error)]
+#[error("Malformed {encoding} sequence in the text")]
+pub struct TextDecodeError {
+    pub encoding: &'static str,
+}
+
+/// Text decoded from a test file, remembering the encoding it was stored in.
+///
+/// When read as a [`TestInput`], a byte order mark determines the encoding
+/// and falls back to UTF-8 otherwise.
+/// Use [`Encoded`] or [`Text::decode`] to declare the encoding of legacy files.
+///
+/// As a [`Snapshot`], only the decoded text is compared, and the snapshot is
+/// written in the original encoding (including the byte order mark, if any)
+/// and read back in the same encoding.
+///
+/// # Example
+///
+/// ```rust
+/// use testdata::encoding_rs::WINDOWS_1252;
+/// use testdata::Text;
+///
+/// let input = Text::decode(b"caf\xE9\n", WINDOWS_1252).unwrap();
+/// assert_eq!(input.text, "café\n");
+///
+/// let output = input.with_text(input.to_uppercase());
+/// assert_eq!(output.encode(), b"CAF\xC9\n");
+/// ```
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "encoding")))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Text {
+    /// The decoded text.
+    pub text: String,
+    /// The encoding the text is stored in.
+    pub encoding: &'static Encoding,
+    /// Whether the byte order mark is present.
+    pub bom: bool,
+}
+
+impl Text {
+    /// Creates a UTF-8 text without the byte order mark.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            encoding: UTF_8,
+            bom: false,
+        }
+    }
+
+    /// Decodes the bytes, assuming `encoding` unless there is a byte order mark.
+    pub fn decode(data: &[u8], encoding: &'static Encoding) -> Result<Self, TextDecodeError> {
+        let (encoding, bom, data) = match Encoding::for_bom(data) {
+            Some((encoding, bom_len)) => (encoding, true, &data[bom_len..]),
+            None => (encoding, false, data),
+        };
+        let text = encoding
+            .decode_without_bom_handling_and_without_replacement(data)
+            .ok_or(TextDecodeError {
+                encoding: encoding.name(),
+            })?;
+        Ok(Self {
+            text: text.into_owned(),
+            encoding,
+            bom,
+        })
+    }
+
+    /// Creates another text in the same encoding as `self`.
+    pub fn with_text(&self, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            encoding: self.encoding,
+            bom: self.bom,
+        }
+    }
+
+    /// Encodes the text back into its encoding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the text contains a character unrepresentable in the encoding.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.text.len() + 3);
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            // encoding_rs does not have UTF-16 encoders
+            let le = self.encoding == UTF_16LE;
+            let units = self
+                .bom
+                .then_some(0xFEFF)
+                .into_iter()
+                .chain(self.text.encode_utf16());
+            for unit in units {
+                let unit = if le {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                };
+                bytes.extend_from_slice(&unit);
+            }
+            return bytes;
+        }
+        if self.bom && self.encoding == UTF_8 {
+            bytes.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        let (encoded, _, unmappable) = self.encoding.encode(&self.text);
+        if unmappable {
+            panic!("The text is not representable in {}", self.encoding.name());
+        }
+        bytes.extend_from_slice(&encoded);
+        bytes
+    }
+}
+
+impl Deref for Text {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.text
+    }
+}
+
+impl Borrow<str> for Text {
+    fn borrow(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for Text {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl TestInput for Text {
+    type Err = TextDecodeError;
+
+    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+        Self::decode(data, UTF_8)
+    }
+}
+
+impl Snapshot for Text {
+    type Borrowed = str;
+    type Owned = Text;
+
+    fn borrow(&self) -> &Self::Borrowed {
+        &self.text
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.encode()
+    }
+
+    /// Decodes the snapshot in the encoding of `self`, unless there is a byte order mark.
+    fn read_snapshot(&self, data: &[u8], _test_file: &TestFile) -> Result<Text, TextDecodeError> {
+        Self::decode(data, self.encoding)
+    }
+}
+
+/// [`Text`] in the encoding declared by `E`, unless there is a byte order mark.
+///
+/// This lets [`testdata::files`](crate::files) read legacy files, as the encoding
+/// is part of the type. The markers for the common encodings are in [`encodings`](crate::encodings).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[testdata::files]
+/// #[test]
+/// fn test_foo(
+///     #[glob = "tests/fixtures/**/*-in.txt"] input: Encoded<Windows1252>,
+///     #[glob = "tests/fixtures/**/*-out.txt"] output: &TestFile,
+/// ) {
+///     let result = input.with_text(input.to_uppercase());
+///     assert_snapshot!(result, snapshot = output);
+/// }
+/// ```
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "encoding")))]
+pub struct Encoded<E>(pub Text, PhantomData<fn() -> E>);
+
+impl<E> Encoded<E> {
+    pub fn new(text: Text) -> Self {
+        Encoded(text, PhantomData)
+    }
+
+    pub fn into_inner(self) -> Text {
+        self.0
+    }
+}
+
+impl<E> fmt::Debug for Encoded<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Encoded").field(&self.0).finish()
+    }
+}
+
+impl<E> Clone for Encoded<E> {
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+impl<E> PartialEq for Encoded<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<E> Eq for Encoded<E> {}
+
+impl<E> Deref for Encoded<E> {
+    type Target = Text;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E> fmt::Display for Encoded<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<E: DeclaredEncoding> TestInput for Encoded<E> {
+    type Err = TextDecodeError;
+
+    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+        Text::decode(data, E::encoding()).map(Self::new)
+    }
+}
+
+impl<E> Snapshot for Encoded<E> {
+    type Borrowed = str;
+    type Owned = Text;
+
+    fn borrow(&self) -> &Self::Borrowed {
+        &self.0.text
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+
+    fn read_snapshot(&self, data: &[u8], test_file: &TestFile) -> Result<Text, TextDecodeError> {
+        self.0.read_snapshot(data, test_file)
+    }
+}
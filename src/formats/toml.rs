@@ -0,0 +1,57 @@
+use std::str;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::formats::serialized::{serialized_newtype, Format};
+use crate::input_error::Position;
+#[cfg(feature = "json")]
+use crate::redactions::{redact_value, Redaction};
+
+serialized_newtype!(
+    /// A TOML document, read and written through serde.
+    ///
+    /// The snapshot is pretty-printed with the keys sorted.
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "toml")))]
+    Toml,
+    TomlFormat
+);
+
+/// The format of [`Toml`], for use with [`Serialized`](crate::Serialized).
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "toml")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TomlFormat;
+
+impl Format for TomlFormat {
+    type Error = toml::de::Error;
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Self::Error> {
+        let data = str::from_utf8(data).map_err(<toml::de::Error as serde::de::Error>::custom)?;
+        toml::from_str(data)
+    }
+
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+        // Going through `toml::Value` sorts the keys
+        let value = toml::Value::try_from(value).unwrap();
+        let mut s = toml::to_string_pretty(&value).unwrap();
+        if !s.ends_with('\n') {
+            s.push('\n');
+        }
+        s.into_bytes()
+    }
+
+    fn error_position(data: &[u8], err: &Self::Error) -> Option<Position> {
+        match (err.span(), str::from_utf8(data)) {
+            (Some(span), _) => Some(Position::Offset(span.start)),
+            (None, Err(e)) => Some(Position::Offset(e.valid_up_to())),
+            (None, Ok(_)) => None,
+        }
+    }
+
+    #[cfg(feature = "json")]
+    fn redact<T: Serialize + ?Sized>(value: &T, redactions: &[Redaction]) -> Option<Vec<u8>> {
+        // Going through `toml::Value` first drops `None`, which has no JSON counterpart in TOML
+        let value = toml::Value::try_from(value).unwrap();
+        Some(Self::encode(&redact_value(&value, redactions)))
+    }
+}
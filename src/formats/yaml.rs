@@ -0,0 +1,59 @@
+use std::cmp::Ordering;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_norway::{Mapping, Value};
+
+use crate::formats::serialized::{serialized_newtype, Format};
+use crate::input_error::Position;
+
+serialized_newtype!(
+    /// A YAML document, read and written through serde.
+    ///
+    /// The snapshot has the keys of the mappings sorted.
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "yaml")))]
+    Yaml,
+    YamlFormat
+);
+
+/// The format of [`Yaml`], for use with [`Serialized`](crate::Serialized).
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "yaml")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct YamlFormat;
+
+impl Format for YamlFormat {
+    type Error = serde_norway::Error;
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Self::Error> {
+        serde_norway::from_slice(data)
+    }
+
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+        let value = sort_keys(serde_norway::to_value(value).unwrap());
+        serde_norway::to_string(&value).unwrap().into_bytes()
+    }
+
+    fn error_position(_data: &[u8], err: &Self::Error) -> Option<Position> {
+        err.location()
+            .map(|location| Position::Offset(location.index()))
+    }
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Mapping(mapping) => {
+            let mut entries = mapping
+                .into_iter()
+                .map(|(k, v)| (k, sort_keys(v)))
+                .collect::<Vec<_>>();
+            entries.sort_by(|(k1, _), (k2, _)| k1.partial_cmp(k2).unwrap_or(Ordering::Equal));
+            Value::Mapping(entries.into_iter().collect::<Mapping>())
+        }
+        Value::Sequence(seq) => Value::Sequence(seq.into_iter().map(sort_keys).collect()),
+        Value::Tagged(mut tagged) => {
+            tagged.value = sort_keys(tagged.value);
+            Value::Tagged(tagged)
+        }
+        value => value,
+    }
+}
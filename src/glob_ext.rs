@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use testdata_rt::GlobSpec;
 
 use crate::test_files::TestFile;
@@ -10,9 +12,11 @@ pub trait GlobSpecExt {
 impl GlobSpecExt for GlobSpec {
     fn expand(&self, stem: &str) -> Option<Vec<TestFile>> {
         let test_files = self.expand_core(stem)?;
+        let spec = Arc::new(self.clone());
         let test_files = test_files
             .into_iter()
-            .map(|paths| TestFile { paths })
+            .enumerate()
+            .map(|(i, paths)| TestFile::new(paths).with_case(spec.clone(), stem, i))
             .collect::<Vec<_>>();
         if test_files.iter().any(|f| f.exists()) {
             Some(test_files)
@@ -0,0 +1,80 @@
+use std::fmt::Write as _;
+
+const ROW_SIZE: usize = 16;
+/// Rows shown before the first differing row.
+const ROWS_BEFORE: usize = 2;
+/// Rows shown from the first differing row.
+const ROWS_AFTER: usize = 8;
+
+/// Describes the difference between two byte strings, with a side-by-side hexdump
+/// around the first differing offset.
+///
+/// Returns `None` if they are equal.
+pub(crate) fn hexdump_diff(actual: &[u8], expected: &[u8]) -> Option<String> {
+    let first_diff = actual
+        .iter()
+        .zip(expected)
+        .position(|(a, e)| a != e)
+        .or_else(|| (actual.len() != expected.len()).then(|| actual.len().min(expected.len())))?;
+
+    let mut message = format!(
+        "first difference at byte offset {} (0x{:x})",
+        first_diff, first_diff
+    );
+    if actual.len() != expected.len() {
+        write!(
+            message,
+            "; expected {} bytes, got {} bytes",
+            expected.len(),
+            actual.len()
+        )
+        .unwrap();
+    }
+    message.push('\n');
+
+    let total_rows = actual.len().max(expected.len()).div_ceil(ROW_SIZE);
+    let first_row = (first_diff / ROW_SIZE).saturating_sub(ROWS_BEFORE);
+    let last_row = (first_diff / ROW_SIZE + ROWS_AFTER).min(total_rows);
+    let width = ROW_SIZE * 3 - 1;
+    writeln!(
+        message,
+        "  {:8}  {:width$}  actual",
+        "offset",
+        "expected",
+        width = width
+    )
+    .unwrap();
+    for row in first_row..last_row {
+        let start = row * ROW_SIZE;
+        let expected_row = row_slice(expected, start);
+        let actual_row = row_slice(actual, start);
+        let marker = if expected_row == actual_row { ' ' } else { '!' };
+        let line = format!(
+            "{} {:08x}  {:width$}  {}",
+            marker,
+            start,
+            hex(expected_row),
+            hex(actual_row),
+            width = width
+        );
+        writeln!(message, "{}", line.trim_end()).unwrap();
+    }
+    if last_row < total_rows {
+        message.push_str("  ...\n");
+    }
+    Some(message)
+}
+
+fn row_slice(data: &[u8], start: usize) -> &[u8] {
+    let start = start.min(data.len());
+    let end = (start + ROW_SIZE).min(data.len());
+    &data[start..end]
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
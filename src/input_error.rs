@@ -0,0 +1,144 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::test_input::TestInput;
+
+/// A location in the input, as reported by [`TestInput::error_position`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// Byte offset from the beginning of the input.
+    Offset(usize),
+    /// Line and column, both 1-based. The column counts bytes.
+    LineColumn { line: usize, column: usize },
+}
+
+impl Position {
+    /// Converts a position in `data[start..]` into the one in `data`.
+    pub(crate) fn offset_by(self, data: &[u8], start: usize) -> Position {
+        match self {
+            Position::Offset(offset) => Position::Offset(start + offset),
+            Position::LineColumn { line, column } => {
+                let prefix = &data[..start];
+                let prefix_lines = prefix.iter().filter(|&&b| b == b'\n').count();
+                let column = if line == 1 {
+                    let line_start = prefix
+                        .iter()
+                        .rposition(|&b| b == b'\n')
+                        .map_or(0, |i| i + 1);
+                    column + start - line_start
+                } else {
+                    column
+                };
+                Position::LineColumn {
+                    line: line + prefix_lines,
+                    column,
+                }
+            }
+        }
+    }
+}
+
+/// An error from [`TestInput`], with the file and the location in it.
+///
+/// Its `Display` renders an excerpt of the input with a caret under the failing position:
+///
+/// ```text
+/// Cannot parse tests/fixtures/foo-in.json:2:8: expected value at line 2 column 8
+///   |
+/// 2 |   "a": ?
+///   |        ^
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestInputError {
+    /// Path to the file, if known.
+    pub path: Option<PathBuf>,
+    /// 1-based line number, if known.
+    pub line: Option<usize>,
+    /// 1-based column number in bytes, if known.
+    pub column: Option<usize>,
+    /// The message from the original error.
+    pub message: String,
+    excerpt: Option<String>,
+}
+
+impl TestInputError {
+    /// Locates the error from `T::try_read_from(data)` in the data.
+    pub fn new<T: TestInput>(data: &[u8], err: &T::Err) -> Self {
+        let mut error = Self {
+            path: None,
+            line: None,
+            column: None,
+            message: err.to_string(),
+            excerpt: None,
+        };
+        if let Some(position) = T::error_position(data, err) {
+            let (line, column) = line_column(data, position);
+            error.line = Some(line);
+            error.column = Some(column);
+            error.excerpt = excerpt(data, line, column);
+        }
+        error
+    }
+
+    /// Sets the path to the file.
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl fmt::Display for TestInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Cannot parse ")?;
+        if let Some(path) = &self.path {
+            write!(f, "{}", path.display())?;
+        } else {
+            f.write_str("input")?;
+        }
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, ":{}:{}", line, column)?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(excerpt) = &self.excerpt {
+            write!(f, "\n{}", excerpt)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TestInputError {}
+
+/// Converts the position into 1-based line and column.
+pub(crate) fn line_column(data: &[u8], position: Position) -> (usize, usize) {
+    match position {
+        Position::Offset(offset) => {
+            let offset = offset.min(data.len());
+            let before = &data[..offset];
+            let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+            let line_start = before
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(0, |i| i + 1);
+            (line, offset - line_start + 1)
+        }
+        Position::LineColumn { line, column } => (line.max(1), column.max(1)),
+    }
+}
+
+/// Renders the line with a caret under the column.
+fn excerpt(data: &[u8], line: usize, column: usize) -> Option<String> {
+    let text = data.split(|&b| b == b'\n').nth(line - 1)?;
+    let text = text.strip_suffix(b"\r").unwrap_or(text);
+    let prefix = &text[..(column - 1).min(text.len())];
+    let indent = String::from_utf8_lossy(prefix).chars().count();
+    let text = String::from_utf8_lossy(text);
+    let gutter = " ".repeat(line.to_string().len());
+    Some(format!(
+        "{gutter} |\n{line} | {text}\n{gutter} | {caret:>width$}",
+        gutter = gutter,
+        line = line,
+        text = text,
+        caret = "^",
+        width = indent + 1,
+    ))
+}
@@ -24,26 +24,80 @@
 //!
 //! More documents will be added in the later versions.
 
+#![allow(clippy::test_attr_in_doctest)]
+
+mod compression;
+mod delimited;
+mod diagnostics;
+#[cfg(any(feature = "encoding", all(feature = "__doc_cfg", doc)))]
+#[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "encoding")))]
+pub mod encodings;
 mod formats;
 mod glob_ext;
+mod hexdump;
+mod input_error;
+mod lock;
+mod markdown;
+#[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
+mod records;
+mod redactions;
+mod review;
+mod sandbox;
+mod sections;
 mod snapshots;
 mod test_files;
 mod test_input;
 pub mod util;
 
-#[cfg(any(feature = "serde_json", all(feature = "__doc_cfg", doc)))]
-pub use crate::formats::json::Json;
+pub use crate::diagnostics::{
+    assert_diagnostics_helper, parse_annotations, rewrite_annotations, Annotation, AnnotationError,
+    Diagnostic, DiagnosticsDiff,
+};
+pub use crate::formats::adapters::{Lines, ParseError, ParseErrorKind, Parsed, Tokens, Trimmed};
+#[cfg(any(feature = "serde", all(feature = "__doc_cfg", doc)))]
+pub use crate::formats::any::{AnyFormat, AnyFormatError};
+pub use crate::formats::formatted::{DebugSnapshot, DisplaySnapshot};
+pub use crate::formats::front_matter::{FrontMatter, FrontMatterError};
+#[cfg(any(feature = "image", all(feature = "__doc_cfg", doc)))]
+pub use crate::formats::image::{Image, ImageDecodeError};
+#[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
+pub use crate::formats::json::{Json, JsonFormat, SortedJson, SortedJsonFormat};
+#[cfg(any(feature = "ron", all(feature = "__doc_cfg", doc)))]
+pub use crate::formats::ron::{Ron, RonFormat};
+#[cfg(any(feature = "serde", all(feature = "__doc_cfg", doc)))]
+pub use crate::formats::serialized::{Format, Serialized};
+#[cfg(any(feature = "encoding", all(feature = "__doc_cfg", doc)))]
+pub use crate::formats::text::{Encoded, Text, TextDecodeError};
+#[cfg(any(feature = "toml", all(feature = "__doc_cfg", doc)))]
+pub use crate::formats::toml::{Toml, TomlFormat};
+#[cfg(any(feature = "yaml", all(feature = "__doc_cfg", doc)))]
+pub use crate::formats::yaml::{Yaml, YamlFormat};
 pub use crate::glob_ext::GlobSpecExt;
-pub use crate::snapshots::{assert_snapshot_helper, Snapshot, SnapshotMode};
+pub use crate::input_error::{Position, TestInputError};
+pub use crate::redactions::{assert_snapshot_redacted_helper, Redaction};
+pub use crate::review::PendingSnapshot;
+pub use crate::sandbox::{sandbox, Sandbox};
+pub use crate::sections::{Section, SectionError, Sections};
+pub use crate::snapshots::{
+    assert_snapshot_helper, assert_snapshot_stream, Snapshot, SnapshotMode,
+};
 pub use crate::test_files::{pending, TestFile};
-pub use crate::test_input::TestInput;
-pub use testdata_macros::files;
+pub use crate::test_input::{FromTestFile, TestInput};
+pub use testdata_macros::{files, records, TestInput};
 pub use testdata_rt::*;
 
+#[cfg(any(feature = "encoding", all(feature = "__doc_cfg", doc)))]
+pub use encoding_rs;
+
 pub mod __rt {
     pub use once_cell::sync::Lazy;
-    pub use testdata_rt::{ArgSpec, GlobSpec};
+    pub use testdata_rt::{ArgSpec, Compression, GlobSpec};
 
+    #[cfg(feature = "json")]
+    pub use crate::records::record_field;
+    pub use crate::snapshots::assert_snapshot_eq;
     pub use crate::util::{diff, touch};
-    pub use crate::GlobSpecExt;
+    pub use crate::{FromTestFile, GlobSpecExt, Position, SectionError, Sections, TestInput};
+    #[cfg(feature = "json")]
+    pub use testdata_rt::RecordSpec;
 }
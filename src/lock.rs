@@ -0,0 +1,173 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use path_slash::PathExt as _;
+use sha2::{Digest, Sha256};
+use testdata_rt::GlobSpec;
+
+use crate::test_files::TestFile;
+
+const HEADER: &str = "\
+# This file is generated by testdata to detect accidental changes to the input files.
+# Run the tests with UPDATE_FIXTURE_LOCK=1 to update it.
+";
+
+/// A lock file and a suite name.
+type SuiteKey = (PathBuf, String);
+
+/// Results of the verification, per lock file and suite.
+static VERIFIED: Lazy<Mutex<HashMap<SuiteKey, Result<(), String>>>> = Lazy::new(Default::default);
+
+/// Checks the input files of the suite against the lock file, if any.
+///
+/// The suite is verified only once in the process. The lock file is updated
+/// instead if `UPDATE_FIXTURE_LOCK` is set.
+pub(crate) fn verify(spec: &GlobSpec) -> io::Result<()> {
+    let lock_file = if let Some(lock_file) = &spec.lock_file {
+        lock_file
+    } else {
+        return Ok(());
+    };
+    let suite = spec.name.as_deref().unwrap_or_default();
+    let mut verified = VERIFIED.lock().unwrap_or_else(|e| e.into_inner());
+    verified
+        .entry((lock_file.clone(), suite.to_owned()))
+        .or_insert_with(|| verify_suite(spec, lock_file, suite))
+        .clone()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn verify_suite(spec: &GlobSpec, lock_file: &Path, suite: &str) -> Result<(), String> {
+    let update_lock = env::var("UPDATE_FIXTURE_LOCK").unwrap_or_default();
+    let update_lock = update_lock == "1" || update_lock == "true";
+
+    let current = hash_inputs(spec)?;
+    let mut entries = read_lock_file(lock_file)
+        .map_err(|e| format!("Cannot read {}: {}", lock_file.display(), e))?;
+
+    if update_lock {
+        entries.retain(|(entry_suite, _), _| entry_suite != suite);
+        for (path, hash) in current {
+            entries.insert((suite.to_owned(), path), hash);
+        }
+        return write_lock_file(lock_file, &entries)
+            .map_err(|e| format!("Cannot write {}: {}", lock_file.display(), e));
+    }
+
+    let recorded = entries
+        .iter()
+        .filter(|((entry_suite, _), _)| entry_suite == suite)
+        .map(|((_, path), hash)| (path, hash))
+        .collect::<BTreeMap<_, _>>();
+    let paths = recorded
+        .keys()
+        .copied()
+        .chain(current.keys())
+        .collect::<BTreeSet<_>>();
+    let mut changes = String::new();
+    for path in paths {
+        let change = match (recorded.get(path), current.get(path)) {
+            (Some(&recorded), Some(current)) if recorded != current => "modified",
+            (None, Some(_)) => "added",
+            (Some(_), None) => "removed",
+            _ => continue,
+        };
+        writeln!(changes, "    {}: {}", change, path).unwrap();
+    }
+    if changes.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Input files differ from {}:\n{}\
+            Run the tests with UPDATE_FIXTURE_LOCK=1 if the changes are intended.",
+            lock_file.display(),
+            changes
+        ))
+    }
+}
+
+/// Computes the hashes of the input files, keyed by the path relative to the root.
+fn hash_inputs(spec: &GlobSpec) -> Result<BTreeMap<String, String>, String> {
+    let mut hashes = BTreeMap::new();
+    let stems = spec.glob().map_err(|e| e.to_string())?;
+    for stem in &stems {
+        let paths = if let Some(paths) = spec.expand_core(stem) {
+            paths
+        } else {
+            continue;
+        };
+        for (arg, paths) in spec.args.iter().zip(paths) {
+            if !arg.input {
+                continue;
+            }
+            // Without the case attached, so that reading does not recurse
+            let test_file = TestFile::new(paths);
+            let contents = match test_file.try_raw_read() {
+                Ok(contents) => contents,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.to_string()),
+            };
+            let path = test_file.path().unwrap();
+            let path = path.strip_prefix(&spec.root).unwrap_or(path);
+            let path = path
+                .to_slash()
+                .ok_or_else(|| format!("Got a non-utf8 path: {:?}", path))?;
+            hashes.insert(path.into_owned(), hex_digest(&contents));
+        }
+    }
+    Ok(hashes)
+}
+
+fn hex_digest(contents: &[u8]) -> String {
+    let mut hex = String::with_capacity(64);
+    for byte in Sha256::digest(contents) {
+        write!(hex, "{:02x}", byte).unwrap();
+    }
+    hex
+}
+
+/// Reads the lock file, consisting of lines in the form of `<sha256> <suite> <path>`.
+fn read_lock_file(lock_file: &Path) -> io::Result<BTreeMap<(String, String), String>> {
+    let contents = match fs::read_to_string(lock_file) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(e),
+    };
+    let mut entries = BTreeMap::new();
+    for line in contents.lines() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let entry = line
+            .split_once(' ')
+            .and_then(|(hash, rest)| Some((hash, rest.split_once(' ')?)));
+        let (hash, (suite, path)) = entry.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Malformed line: {:?}", line),
+            )
+        })?;
+        entries.insert((suite.to_owned(), path.to_owned()), hash.to_owned());
+    }
+    Ok(entries)
+}
+
+fn write_lock_file(
+    lock_file: &Path,
+    entries: &BTreeMap<(String, String), String>,
+) -> io::Result<()> {
+    let mut contents = String::from(HEADER);
+    for ((suite, path), hash) in entries {
+        writeln!(contents, "{} {} {}", hash, suite, path).unwrap();
+    }
+    if let Some(parent) = lock_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(lock_file, contents)
+}
@@ -0,0 +1,111 @@
+use std::io;
+use std::str;
+
+use testdata_rt::{parse_markdown, MarkdownCase};
+
+/// Extracts the contents of the `index`-th code block in the section.
+pub(crate) fn read_block(src: &[u8], slug: &str, index: usize) -> io::Result<Vec<u8>> {
+    let src = to_str(src)?;
+    let case = find_case(src, slug)?;
+    let block = case.blocks.get(index).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Section {:?} has no code block #{}", slug, index),
+        )
+    })?;
+    Ok(src[block.contents.clone()].as_bytes().to_owned())
+}
+
+/// Replaces the contents of the `index`-th code block in the section.
+///
+/// If the section has exactly `index` blocks, a new block is added after the last one.
+pub(crate) fn write_block(
+    src: &[u8],
+    slug: &str,
+    index: usize,
+    contents: &[u8],
+) -> io::Result<Vec<u8>> {
+    let src = to_str(src)?;
+    let contents = to_str(contents)?;
+    let case = find_case(src, slug)?;
+    let mut contents = contents.to_owned();
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    let mut result = String::with_capacity(src.len() + contents.len());
+    if let Some(block) = case.blocks.get(index) {
+        // The fences are lengthened if the contents would close them
+        let before = &src[..block.contents.start];
+        let open_start = before
+            .strip_suffix('\n')
+            .unwrap_or(before)
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let open = &src[open_start..block.contents.start];
+        let close = &src[block.contents.end..block.end];
+        let ch = open.trim_start_matches(' ').chars().next().unwrap();
+        let len = fence_for(&contents, ch).len();
+        result.push_str(&src[..open_start]);
+        result.push_str(&lengthen_fence(open, ch, len));
+        result.push_str(&contents);
+        result.push_str(&lengthen_fence(close, ch, len));
+        result.push_str(&src[block.end..]);
+    } else if index == case.blocks.len() {
+        let last = case.blocks.last().unwrap();
+        let fence = fence_for(&contents, '`');
+        result.push_str(&src[..last.end]);
+        result.push_str(&format!("\n{}\n{}{}\n", fence, contents, fence));
+        result.push_str(&src[last.end..]);
+    } else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Section {:?} has only {} code blocks, cannot add #{}",
+                slug,
+                case.blocks.len(),
+                index
+            ),
+        ));
+    }
+    Ok(result.into_bytes())
+}
+
+fn find_case(src: &str, slug: &str) -> io::Result<MarkdownCase> {
+    parse_markdown(src)
+        .into_iter()
+        .find(|case| case.slug == slug)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No section {:?} with code blocks", slug),
+            )
+        })
+}
+
+/// Chooses a fence longer than any run of `ch` starting a line in the contents.
+fn fence_for(contents: &str, ch: char) -> String {
+    let longest = contents
+        .lines()
+        .map(|line| {
+            let line = line.trim_start();
+            line.len() - line.trim_start_matches(ch).len()
+        })
+        .max()
+        .unwrap_or(0);
+    ch.to_string().repeat(longest.max(2) + 1)
+}
+
+/// Makes the fence on the line at least `len` long, keeping the indentation and the rest.
+fn lengthen_fence(line: &str, ch: char, len: usize) -> String {
+    let fence = line.trim_start_matches(' ');
+    let indent = &line[..line.len() - fence.len()];
+    let rest = fence.trim_start_matches(ch);
+    if line.is_empty() || fence.len() - rest.len() >= len {
+        return line.to_owned();
+    }
+    format!("{}{}{}", indent, ch.to_string().repeat(len), rest)
+}
+
+fn to_str(data: &[u8]) -> io::Result<&str> {
+    str::from_utf8(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
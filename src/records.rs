@@ -0,0 +1,106 @@
+use serde::de::value::{Error, StrDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use testdata_rt::{Record, RecordSpec, RecordValue};
+
+/// Reads the field of the record as a parameter of the test function.
+///
+/// JSON values are deserialized as is. CSV values are parsed according to the type,
+/// and an empty value is read as `None` for `Option<T>`.
+/// A missing field is read as `None` as well.
+///
+/// # Panics
+///
+/// If the field cannot be read as `T`.
+#[doc(hidden)]
+pub fn record_field<T: DeserializeOwned>(spec: &RecordSpec, record: &Record, name: &str) -> T {
+    let result = match record.get(name) {
+        Some(RecordValue::Json(value)) => T::deserialize(value).map_err(|e| e.to_string()),
+        Some(RecordValue::Text(text)) => {
+            T::deserialize(TextDeserializer(text)).map_err(|e| e.to_string())
+        }
+        None => T::deserialize(&serde_json::Value::Null).map_err(|_| "Missing field".to_owned()),
+    };
+    result.unwrap_or_else(|message| {
+        panic!(
+            "Cannot read field {:?} of record {:?} at {}:{}: {}",
+            name,
+            record.name,
+            spec.path().display(),
+            record.line,
+            message
+        )
+    })
+}
+
+/// Deserializes a CSV field, parsing it if a primitive type is requested.
+struct TextDeserializer<'a>(&'a str);
+
+macro_rules! parse_primitives {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.0.trim().parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for TextDeserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_str(self.0)
+    }
+
+    parse_primitives! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let deserializer: StrDeserializer<Error> = self.0.into_deserializer();
+        visitor.visit_enum(deserializer)
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
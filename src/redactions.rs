@@ -0,0 +1,378 @@
+use std::borrow::Borrow;
+#[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+use std::env;
+#[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+use std::path::Path;
+
+#[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+use regex::bytes::{NoExpand, Regex};
+use testdata_rt::pretty_assertions::StrComparison;
+
+use crate::hexdump::hexdump_diff;
+use crate::snapshots::{
+    fail_snapshot, pass_snapshot, read_expected, read_or_write_snapshot, review_note,
+    write_snapshot, Snapshot, SnapshotMode,
+};
+use crate::test_files::TestFile;
+
+/// A replacement applied to the actual value before it is compared with or written as a snapshot.
+///
+/// Use it with the `redact` option of [`assert_snapshot!`](crate::assert_snapshot)
+/// to hide the parts that change between runs, like timestamps or temporary paths.
+///
+/// The text-based redactions require the `regex` feature,
+/// and the JSON pointer redactions require the `json` feature.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_snapshot!(
+///     output,
+///     snapshot = expected,
+///     redact = [
+///         Redaction::timestamps(),
+///         Redaction::manifest_dir(),
+///         Redaction::regex(r"pid=\d+", "pid=[pid]"),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Redaction {
+    kind: RedactionKind,
+}
+
+#[derive(Debug, Clone)]
+enum RedactionKind {
+    #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+    Regex {
+        regex: Regex,
+        replacement: String,
+        expand: bool,
+    },
+    #[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
+    Pointer {
+        tokens: Vec<String>,
+        replacement: String,
+    },
+}
+
+impl Redaction {
+    /// Replaces the matches of the regular expression in the snapshot.
+    ///
+    /// The replacement may refer to the capture groups, like `$1` or `${name}`.
+    ///
+    /// # Panics
+    ///
+    /// If the pattern is invalid.
+    #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "regex")))]
+    pub fn regex(pattern: &str, replacement: &str) -> Self {
+        Self::try_regex(pattern, replacement).unwrap()
+    }
+
+    /// Replaces the matches of the regular expression in the snapshot.
+    #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "regex")))]
+    pub fn try_regex(pattern: &str, replacement: &str) -> Result<Self, regex::Error> {
+        Ok(Self::from_regex(Regex::new(pattern)?, replacement, true))
+    }
+
+    /// Replaces the occurrences of the text in the snapshot.
+    #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "regex")))]
+    pub fn literal(text: &str, replacement: &str) -> Self {
+        Self::from_regex(
+            Regex::new(&regex::escape(text)).unwrap(),
+            replacement,
+            false,
+        )
+    }
+
+    /// Replaces date-times like `2022-09-25T12:34:56.789Z` with `[timestamp]`.
+    #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "regex")))]
+    pub fn timestamps() -> Self {
+        Self::regex(
+            r"\b\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:?\d{2})?",
+            "[timestamp]",
+        )
+    }
+
+    /// Replaces UUIDs like `67e55044-10b1-426f-9247-bb680e5fe0c8` with `[uuid]`.
+    #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "regex")))]
+    pub fn uuids() -> Self {
+        Self::regex(
+            r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+            "[uuid]",
+        )
+    }
+
+    /// Replaces the temporary directory of the system with `[temp_dir]`.
+    #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "regex")))]
+    pub fn temp_dir() -> Self {
+        let dir = env::temp_dir();
+        let mut dirs = vec![dir.clone()];
+        // Some systems reach it through a symbolic link, like `/var` to `/private/var`
+        if let Ok(canonical) = dir.canonicalize() {
+            if canonical != dir {
+                dirs.push(canonical);
+            }
+        }
+        Self::paths(&dirs, "[temp_dir]")
+    }
+
+    /// Replaces `CARGO_MANIFEST_DIR` of the package under test with `[manifest_dir]`.
+    #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "regex")))]
+    pub fn manifest_dir() -> Self {
+        let dirs = env::var_os("CARGO_MANIFEST_DIR")
+            .map(|dir| vec![Path::new(&dir).to_owned()])
+            .unwrap_or_default();
+        Self::paths(&dirs, "[manifest_dir]")
+    }
+
+    /// Replaces the value at the JSON pointer, like `/items/0/id`, with the string.
+    ///
+    /// The token `*` matches any key or index, like `/items/*/id`.
+    /// This is only supported by the serde-based formats, and a pointer to no value is ignored.
+    ///
+    /// # Panics
+    ///
+    /// If the pointer is neither empty nor starts with `/`.
+    #[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "json")))]
+    pub fn pointer(pointer: &str, replacement: &str) -> Self {
+        let tokens = match pointer.strip_prefix('/') {
+            Some(rest) => rest
+                .split('/')
+                .map(|token| token.replace("~1", "/").replace("~0", "~"))
+                .collect(),
+            None if pointer.is_empty() => Vec::new(),
+            None => panic!("Invalid JSON pointer: {:?}", pointer),
+        };
+        Self {
+            kind: RedactionKind::Pointer {
+                tokens,
+                replacement: replacement.to_owned(),
+            },
+        }
+    }
+
+    #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+    fn from_regex(regex: Regex, replacement: &str, expand: bool) -> Self {
+        Self {
+            kind: RedactionKind::Regex {
+                regex,
+                replacement: replacement.to_owned(),
+                expand,
+            },
+        }
+    }
+
+    #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+    /// Matches any of the paths, without trailing separators.
+    fn paths(paths: &[impl AsRef<Path>], replacement: &str) -> Self {
+        let mut alternatives = paths
+            .iter()
+            .map(|path| path.as_ref().to_string_lossy())
+            .map(|path| path.trim_end_matches(['/', '\\']).to_owned())
+            .filter(|path| !path.is_empty())
+            .collect::<Vec<_>>();
+        // Longer paths first, so that they are not partially replaced
+        alternatives.sort_by_key(|path| std::cmp::Reverse(path.len()));
+        let pattern = if alternatives.is_empty() {
+            // Never matches
+            String::from(r"[^\s\S]")
+        } else {
+            alternatives
+                .iter()
+                .map(|path| regex::escape(path))
+                .collect::<Vec<_>>()
+                .join("|")
+        };
+        Self::from_regex(Regex::new(&pattern).unwrap(), replacement, false)
+    }
+
+    #[cfg(feature = "json")]
+    fn as_pointer(&self) -> Option<(&[String], &str)> {
+        match &self.kind {
+            #[cfg(feature = "regex")]
+            RedactionKind::Regex { .. } => None,
+            RedactionKind::Pointer {
+                tokens,
+                replacement,
+            } => Some((tokens, replacement)),
+        }
+    }
+
+    fn is_pointer(&self) -> bool {
+        match self.kind {
+            #[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+            RedactionKind::Regex { .. } => false,
+            #[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
+            RedactionKind::Pointer { .. } => true,
+        }
+    }
+}
+
+/// Applies the text-based redactions to the snapshot.
+#[cfg(any(feature = "regex", all(feature = "__doc_cfg", doc)))]
+pub(crate) fn redact_bytes(bytes: &[u8], redactions: &[Redaction]) -> Vec<u8> {
+    redactions
+        .iter()
+        .fold(bytes.to_owned(), |bytes, redaction| match redaction.kind {
+            RedactionKind::Regex {
+                ref regex,
+                ref replacement,
+                expand,
+            } => {
+                let replaced = if expand {
+                    regex.replace_all(&bytes, replacement.as_bytes())
+                } else {
+                    regex.replace_all(&bytes, NoExpand(replacement.as_bytes()))
+                };
+                replaced.into_owned()
+            }
+            #[cfg(any(feature = "json", all(feature = "__doc_cfg", doc)))]
+            RedactionKind::Pointer { .. } => bytes,
+        })
+}
+
+#[cfg(not(any(feature = "regex", all(feature = "__doc_cfg", doc))))]
+pub(crate) fn redact_bytes(bytes: &[u8], _redactions: &[Redaction]) -> Vec<u8> {
+    bytes.to_owned()
+}
+
+/// Converts the value to JSON and applies the pointer redactions to it.
+#[cfg(feature = "json")]
+pub(crate) fn redact_value<T>(value: &T, redactions: &[Redaction]) -> serde_json::Value
+where
+    T: serde::Serialize + ?Sized,
+{
+    let mut value = serde_json::to_value(value).unwrap();
+    for (tokens, replacement) in pointers(redactions) {
+        replace_pointer(&mut value, tokens, replacement);
+    }
+    value
+}
+
+/// Lists the tokens and the replacement of the JSON pointer redactions.
+#[cfg(feature = "json")]
+pub(crate) fn pointers(redactions: &[Redaction]) -> impl Iterator<Item = (&[String], &str)> {
+    redactions.iter().filter_map(Redaction::as_pointer)
+}
+
+#[cfg(feature = "json")]
+fn replace_pointer(value: &mut serde_json::Value, tokens: &[String], replacement: &str) {
+    use serde_json::Value;
+
+    let (token, rest) = if let Some((token, rest)) = tokens.split_first() {
+        (token, rest)
+    } else {
+        *value = Value::String(replacement.to_owned());
+        return;
+    };
+    match value {
+        Value::Object(map) if token == "*" => {
+            for child in map.values_mut() {
+                replace_pointer(child, rest, replacement);
+            }
+        }
+        Value::Object(map) => {
+            if let Some(child) = map.get_mut(token) {
+                replace_pointer(child, rest, replacement);
+            }
+        }
+        Value::Array(items) if token == "*" => {
+            for child in items {
+                replace_pointer(child, rest, replacement);
+            }
+        }
+        Value::Array(items) => {
+            if let Some(child) = token.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                replace_pointer(child, rest, replacement);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The implementation of [`assert_snapshot!`](crate::assert_snapshot) with redactions.
+///
+/// The redacted snapshot is compared with the stored one as bytes, and written
+/// as is with `UPDATE_SNAPSHOTS=all`. Otherwise, if they differ, both are
+/// read back and compared as values, unless the redactions do not fit the type,
+/// in which case the difference in bytes is reported.
+pub fn assert_snapshot_redacted_helper<T, F>(
+    e: &T,
+    test_file: &TestFile,
+    redactions: &[Redaction],
+    assertion: F,
+) where
+    T: Snapshot + ?Sized,
+    T::Borrowed: PartialEq,
+    F: FnOnce(&T::Borrowed, &T::Borrowed),
+{
+    let actual = if redactions.iter().any(Redaction::is_pointer) {
+        e.redact_pointers(redactions).unwrap_or_else(|| {
+            panic!(
+                "JSON pointer redactions are not supported by {}",
+                std::any::type_name::<T>()
+            )
+        })
+    } else {
+        e.to_bytes()
+    };
+    let actual = redact_bytes(&actual, redactions);
+
+    let mode = SnapshotMode::current();
+    let expected =
+        if let Some(expected) = read_or_write_snapshot(e, mode, test_file, || actual.clone()) {
+            expected
+        } else {
+            return;
+        };
+    if actual == expected {
+        pass_snapshot::<T>(e.borrow(), test_file);
+        return;
+    }
+    if mode == SnapshotMode::All {
+        write_snapshot(e, test_file, &actual);
+        return;
+    }
+
+    // The redacted snapshot may not fit the type, like a string in place of a number
+    let actual_value = if let Ok(actual_value) = e.read_snapshot(&actual, test_file) {
+        actual_value
+    } else {
+        let note = review_note(mode, test_file, &actual);
+        let message = match (std::str::from_utf8(&actual), std::str::from_utf8(&expected)) {
+            (Ok(actual), Ok(expected)) => format!(
+                "Diff < actual / snapshot > :\n{}",
+                StrComparison::new(actual, expected)
+            ),
+            _ => hexdump_diff(&actual, &expected).unwrap_or_default(),
+        };
+        panic!(
+            "Snapshot mismatch: {}: {}{}",
+            test_file,
+            message.trim_end(),
+            note
+        );
+    };
+    let expected_value = read_expected(e, &expected, test_file);
+    if actual_value.borrow() == expected_value.borrow() {
+        pass_snapshot::<T>(actual_value.borrow(), test_file);
+        return;
+    }
+    fail_snapshot::<T, F>(
+        mode,
+        test_file,
+        &actual,
+        actual_value.borrow(),
+        expected_value.borrow(),
+        assertion,
+    );
+}
@@ -0,0 +1,109 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str;
+
+use testdata_rt::pretty_assertions::StrComparison;
+use testdata_rt::{is_pending_review, reviewed_path};
+
+use crate::hexdump::hexdump_diff;
+use crate::test_files::{record_review, TestFile};
+
+/// A snapshot written in [`SnapshotMode::Review`](crate::SnapshotMode::Review),
+/// waiting to be accepted or rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingSnapshot {
+    /// Path to the pending snapshot, like `foo-out.txt.new`.
+    pub path: PathBuf,
+    /// Path to the snapshot it replaces, like `foo-out.txt`.
+    pub target: PathBuf,
+}
+
+impl PendingSnapshot {
+    /// Returns the pending snapshot at the path, if the path is one.
+    pub fn new(path: &Path) -> Option<Self> {
+        Some(Self {
+            path: path.to_owned(),
+            target: reviewed_path(path)?,
+        })
+    }
+
+    /// Finds the pending snapshots under the directory, sorted by path.
+    ///
+    /// Only the files recorded by the test runs are found, in the manifest
+    /// of each directory. Hidden directories and `target` are skipped.
+    pub fn find(dir: &Path) -> io::Result<Vec<Self>> {
+        let mut pending = Vec::new();
+        find_in(dir, &mut pending)?;
+        pending.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(pending)
+    }
+
+    /// Reads the pending snapshot.
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        fs::read(&self.path)
+    }
+
+    /// Reads the snapshot to be replaced, possibly compressed, if it exists.
+    pub fn read_target(&self) -> io::Result<Option<Vec<u8>>> {
+        match self.target_file().try_raw_read() {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            result => result.map(Some),
+        }
+    }
+
+    /// Describes the change from the current snapshot to the pending one.
+    pub fn diff(&self) -> io::Result<String> {
+        let new = self.read()?;
+        let old = if let Some(old) = self.read_target()? {
+            old
+        } else {
+            return Ok(format!(
+                "New snapshot:\n{}",
+                String::from_utf8_lossy(&new).trim_end()
+            ));
+        };
+        Ok(match (str::from_utf8(&old), str::from_utf8(&new)) {
+            (Ok(old), Ok(new)) => format!(
+                "Diff < snapshot / pending > :\n{}",
+                StrComparison::new(old, new)
+            ),
+            _ => hexdump_diff(&new, &old).unwrap_or_else(|| String::from("No changes")),
+        })
+    }
+
+    /// Replaces the snapshot with the pending one.
+    ///
+    /// The snapshot stays compressed if it was.
+    pub fn accept(&self) -> io::Result<()> {
+        self.target_file().try_raw_write(&self.read()?)?;
+        self.reject()
+    }
+
+    /// Discards the pending snapshot.
+    pub fn reject(&self) -> io::Result<()> {
+        fs::remove_file(&self.path)?;
+        record_review(&self.path, false)
+    }
+
+    fn target_file(&self) -> TestFile {
+        TestFile::new(vec![self.target.clone()])
+    }
+}
+
+fn find_in(dir: &Path, pending: &mut Vec<PendingSnapshot>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if !name.starts_with('.') && name != "target" {
+                find_in(&path, pending)?;
+            }
+        } else if is_pending_review(&path) {
+            pending.extend(PendingSnapshot::new(&path));
+        }
+    }
+    Ok(())
+}
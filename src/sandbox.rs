@@ -0,0 +1,127 @@
+use std::fs::{self, File};
+use std::io;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use tempfile::TempDir;
+
+use crate::test_files::TestFile;
+
+/// A temporary directory holding writable copies of test files.
+///
+/// Use [`sandbox`] to get one that is kept for inspection when the test fails.
+#[derive(Debug)]
+pub struct Sandbox {
+    dir: TempDir,
+    paths: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    /// Creates an empty sandbox.
+    pub fn new() -> Self {
+        Self::try_new().unwrap()
+    }
+
+    pub fn try_new() -> io::Result<Self> {
+        let dir = tempfile::Builder::new().prefix("testdata-").tempdir()?;
+        Ok(Self {
+            dir,
+            paths: Vec::new(),
+        })
+    }
+
+    /// Returns the root of the sandbox.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Returns the paths to the copies, in the order of [`Sandbox::copy`] calls.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Copies a test file (or a directory) into the sandbox and returns the path to the copy.
+    ///
+    /// The copy keeps the path relative to the root of the test suite if known.
+    /// If the file does not exist, nothing is copied but the path is still reserved.
+    pub fn copy(&mut self, test_file: &TestFile) -> PathBuf {
+        self.try_copy(test_file).unwrap()
+    }
+
+    pub fn try_copy(&mut self, test_file: &TestFile) -> io::Result<PathBuf> {
+        let relative_path = match test_file.relative_path() {
+            Some(relative_path) => relative_path.to_owned(),
+            None => PathBuf::from(test_file.path_for_writing().file_name().unwrap_or_default()),
+        };
+        let dest = self.dir.path().join(relative_path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match test_file.path() {
+            Some(source) if source.is_dir() => copy_recursively(source, &dest)?,
+            // Decompresses the file if necessary
+            Some(_) => {
+                io::copy(&mut test_file.try_open()?, &mut File::create(&dest)?)?;
+            }
+            None => {}
+        }
+        self.paths.push(dest.clone());
+        Ok(dest)
+    }
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn copy_recursively(source: &Path, dest: &Path) -> io::Result<()> {
+    if source.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursively(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(source, dest)?;
+    }
+    Ok(())
+}
+
+/// Runs the test on writable copies of the test files.
+///
+/// The sandbox is removed if `f` returns normally.
+/// Otherwise it is kept, and the panic is raised again with its path appended to the message.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[testdata::files]
+/// #[test]
+/// fn test_format_in_place(#[glob = "tests/fixtures/*.rs"] input: &TestFile) {
+///     sandbox(&[input], |sandbox| {
+///         format_file(&sandbox.paths()[0]);
+///     });
+/// }
+/// ```
+pub fn sandbox<F>(test_files: &[&TestFile], f: F)
+where
+    F: FnOnce(&Sandbox),
+{
+    let mut sandbox = Sandbox::new();
+    for test_file in test_files {
+        sandbox.copy(test_file);
+    }
+    let result = catch_unwind(AssertUnwindSafe(|| f(&sandbox)));
+    if let Err(e) = result {
+        let path = sandbox.dir.keep();
+        // Same as the standard panic hook for payloads other than strings
+        let message = e
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| e.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        panic!("{}\nThe sandbox is kept at: {}", message, path.display());
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+use std::error::Error as StdError;
+
+use thiserror::Error;
+
+use crate::input_error::{line_column, Position};
+use crate::test_input::TestInput;
+
+/// Represents an error in reading a file with sections.
+#[derive(Debug, Error)]
+pub enum SectionError {
+    #[error("Missing section: {name:?}")]
+    Missing { name: String },
+    #[error("Duplicate section {name:?} at line {line}")]
+    Duplicate { name: String, line: usize },
+    #[error("Unknown section {name:?} at line {line}")]
+    Unknown { name: String, line: usize },
+    #[error("Expected a section header like `--- input ---` at line {line}")]
+    NoHeader { line: usize },
+    #[error("Error in section {name:?} at line {line}: {source}")]
+    Parse {
+        name: String,
+        line: usize,
+        #[source]
+        source: Box<dyn StdError + Send + Sync>,
+        /// Position of the error in the whole file, if the section type reports one.
+        position: Option<Position>,
+    },
+}
+
+impl SectionError {
+    /// Locates the error in the whole file.
+    ///
+    /// Errors in a section are located by the [`TestInput::error_position`] of its type,
+    /// shifted by where the section starts.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            SectionError::Missing { .. } => None,
+            SectionError::Duplicate { line, .. }
+            | SectionError::Unknown { line, .. }
+            | SectionError::NoHeader { line } => Some(Position::LineColumn {
+                line: *line,
+                column: 1,
+            }),
+            SectionError::Parse { position, .. } => *position,
+        }
+    }
+}
+
+/// A section in a file, introduced by a header line like `--- input ---`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// Name of the section, like `input`.
+    pub name: &'a str,
+    /// Line number (1-based) of the header.
+    pub line: usize,
+    /// Contents of the section, after the header line.
+    pub data: &'a [u8],
+}
+
+/// A file split into sections.
+///
+/// This is what `#[derive(TestInput)]` uses to read each field from a section.
+///
+/// # Example
+///
+/// ```rust
+/// use testdata::Sections;
+///
+/// let data = b"--- input ---\n1 + 2\n--- output ---\n3\n";
+/// let sections = Sections::parse(data, &["input", "output"]).unwrap();
+/// let input: String = sections.field("input").unwrap();
+/// assert_eq!(input, "1 + 2\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sections<'a> {
+    pub sections: Vec<Section<'a>>,
+}
+
+impl<'a> Sections<'a> {
+    /// Splits the file into sections, allowing only the listed names.
+    ///
+    /// Blank lines before the first header are ignored.
+    pub fn parse(data: &'a [u8], names: &[&str]) -> Result<Self, SectionError> {
+        let mut sections: Vec<Section<'a>> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pos = 0;
+        for (i, line) in data.split_inclusive(|&b| b == b'\n').enumerate() {
+            let line_start = pos;
+            pos += line.len();
+            let lineno = i + 1;
+            if let Some(name) = header_name(line) {
+                if let Some(last) = sections.last_mut() {
+                    let start = data.len() - last.data.len();
+                    last.data = &data[start..line_start];
+                }
+                if !names.contains(&name) {
+                    return Err(SectionError::Unknown {
+                        name: name.to_owned(),
+                        line: lineno,
+                    });
+                }
+                if !seen.insert(name) {
+                    return Err(SectionError::Duplicate {
+                        name: name.to_owned(),
+                        line: lineno,
+                    });
+                }
+                sections.push(Section {
+                    name,
+                    line: lineno,
+                    data: &data[pos..],
+                });
+            } else if sections.is_empty() && !line.trim_ascii().is_empty() {
+                return Err(SectionError::NoHeader { line: lineno });
+            }
+        }
+        Ok(Self { sections })
+    }
+
+    /// Finds a section by name.
+    pub fn get(&self, name: &str) -> Option<&Section<'a>> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Reads a required section.
+    pub fn field<T>(&self, name: &str) -> Result<T, SectionError>
+    where
+        T: TestInput,
+        T::Err: Send + Sync + 'static,
+    {
+        let section = self.get(name).ok_or_else(|| SectionError::Missing {
+            name: name.to_owned(),
+        })?;
+        section.read()
+    }
+
+    /// Reads an optional section.
+    pub fn field_opt<T>(&self, name: &str) -> Result<Option<T>, SectionError>
+    where
+        T: TestInput,
+        T::Err: Send + Sync + 'static,
+    {
+        self.get(name).map(|section| section.read()).transpose()
+    }
+}
+
+impl<'a> Section<'a> {
+    /// Reads the contents through the [`TestInput`] implementation.
+    pub fn read<T>(&self) -> Result<T, SectionError>
+    where
+        T: TestInput,
+        T::Err: Send + Sync + 'static,
+    {
+        T::try_read_from(self.data).map_err(|e| {
+            // The contents start at the line after the header
+            let position = T::error_position(self.data, &e).map(|position| {
+                let (line, column) = line_column(self.data, position);
+                Position::LineColumn {
+                    line: self.line + line,
+                    column,
+                }
+            });
+            SectionError::Parse {
+                name: self.name.to_owned(),
+                line: self.line,
+                source: Box::new(e),
+                position,
+            }
+        })
+    }
+}
+
+/// Recognizes a header line like `--- input ---`.
+fn header_name(line: &[u8]) -> Option<&str> {
+    let name = line
+        .trim_ascii()
+        .strip_prefix(b"--- ")?
+        .strip_suffix(b" ---")?;
+    let name = std::str::from_utf8(name).ok()?.trim();
+    (!name.is_empty()).then_some(name)
+}
@@ -1,18 +1,72 @@
 use std::borrow::Borrow;
 use std::env;
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
 
+use testdata_rt::pretty_assertions;
+
+use crate::hexdump::hexdump_diff;
+use crate::input_error::TestInputError;
+use crate::redactions::Redaction;
 use crate::test_files::TestFile;
-use crate::test_input::TestInput;
+use crate::test_input::{read_test_file, TestInput};
 
+/// Compares the value with the snapshot stored in the test file.
+///
+/// With `redact = [...]`, or any other expression giving a slice of [`Redaction`]s,
+/// they are applied to the actual value before it is compared or written.
 #[macro_export]
 macro_rules! assert_snapshot {
     ($e:expr, snapshot = $test_file:expr) => {
         match (&($e), &($test_file)) {
             (e, test_file) => $crate::assert_snapshot_helper(e, test_file, |lhs, rhs| {
-                $crate::pretty_assertions::assert_eq!(*lhs, *rhs)
+                $crate::__rt::assert_snapshot_eq(e, lhs, rhs, test_file)
             }),
         }
     };
+    ($e:expr, snapshot = $test_file:expr, redact = $redactions:expr) => {
+        match (&($e), &($test_file)) {
+            (e, test_file) => {
+                $crate::assert_snapshot_redacted_helper(e, test_file, &($redactions), |lhs, rhs| {
+                    $crate::__rt::assert_snapshot_eq(e, lhs, rhs, test_file)
+                })
+            }
+        }
+    };
+}
+
+/// Compares the `{:#?}` representation of the value with the snapshot.
+///
+/// Shorthand for `assert_snapshot!(DebugSnapshot::new(&e), snapshot = ...)`.
+#[macro_export]
+macro_rules! assert_debug_snapshot {
+    ($e:expr, snapshot = $test_file:expr) => {
+        $crate::assert_snapshot!($crate::DebugSnapshot::new(&($e)), snapshot = $test_file)
+    };
+    ($e:expr, snapshot = $test_file:expr, redact = $redactions:expr) => {
+        $crate::assert_snapshot!(
+            $crate::DebugSnapshot::new(&($e)),
+            snapshot = $test_file,
+            redact = $redactions
+        )
+    };
+}
+
+/// Compares the `Display` representation of the value with the snapshot.
+///
+/// Shorthand for `assert_snapshot!(DisplaySnapshot::new(&e), snapshot = ...)`.
+#[macro_export]
+macro_rules! assert_display_snapshot {
+    ($e:expr, snapshot = $test_file:expr) => {
+        $crate::assert_snapshot!($crate::DisplaySnapshot::new(&($e)), snapshot = $test_file)
+    };
+    ($e:expr, snapshot = $test_file:expr, redact = $redactions:expr) => {
+        $crate::assert_snapshot!(
+            $crate::DisplaySnapshot::new(&($e)),
+            snapshot = $test_file,
+            redact = $redactions
+        )
+    };
 }
 
 pub fn assert_snapshot_helper<T, F>(e: &T, test_file: &TestFile, assertion: F)
@@ -22,41 +76,269 @@ where
     F: FnOnce(&T::Borrowed, &T::Borrowed),
 {
     let mode = SnapshotMode::current();
-    let expected = if let Some(expected) = test_file.raw_read_opt() {
-        expected
-    } else if mode >= SnapshotMode::New {
-        write_snapshot(e, test_file);
-        return;
-    } else {
-        panic!(
-            "Snapshot does not exist: {}",
-            test_file.path_for_writing().display()
-        );
-    };
+    let expected =
+        if let Some(expected) = read_or_write_snapshot(e, mode, test_file, || e.to_bytes()) {
+            expected
+        } else {
+            return;
+        };
 
-    let expected = T::Owned::read_from(&expected);
+    let expected = read_expected(e, &expected, test_file);
     if *e.borrow() != *expected.borrow() {
         if mode == SnapshotMode::All {
-            write_snapshot(e, test_file);
+            write_snapshot(e, test_file, &e.to_bytes());
             return;
         }
-        assertion(e.borrow(), expected.borrow());
-        unreachable!();
+        fail_snapshot::<T, F>(
+            mode,
+            test_file,
+            &e.to_bytes(),
+            e.borrow(),
+            expected.borrow(),
+            assertion,
+        );
     }
+    pass_snapshot::<T>(e.borrow(), test_file);
 }
 
-fn write_snapshot<T>(e: &T, fixture: &TestFile)
+/// Reads the stored snapshot, or writes the actual one if it does not exist
+/// and the mode allows it, in which case `None` is returned.
+///
+/// # Panics
+///
+/// If the snapshot does not exist and the mode does not allow writing it.
+pub(crate) fn read_or_write_snapshot<T, F>(
+    e: &T,
+    mode: SnapshotMode,
+    test_file: &TestFile,
+    actual: F,
+) -> Option<Vec<u8>>
 where
     T: Snapshot + ?Sized,
+    F: FnOnce() -> Vec<u8>,
+{
+    if let Some(expected) = test_file.raw_read_opt() {
+        return Some(expected);
+    }
+    if mode >= SnapshotMode::New {
+        write_snapshot(e, test_file, &actual());
+        return None;
+    }
+    panic!(
+        "Snapshot does not exist: {}{}",
+        test_file.path_for_writing().display(),
+        review_note(mode, test_file, &actual())
+    );
+}
+
+/// Reads the stored snapshot back as a value to compare with.
+///
+/// # Panics
+///
+/// If the snapshot cannot be read, reporting the position in the file.
+pub(crate) fn read_expected<T>(e: &T, expected: &[u8], test_file: &TestFile) -> T::Owned
+where
+    T: Snapshot + ?Sized,
+{
+    e.read_snapshot(expected, test_file).unwrap_or_else(|e| {
+        panic!(
+            "{}",
+            TestInputError::new::<T::Owned>(expected, &e).with_path(test_file.to_string())
+        )
+    })
+}
+
+pub(crate) fn write_snapshot<T>(e: &T, test_file: &TestFile, bytes: &[u8])
+where
+    T: Snapshot + ?Sized,
+{
+    test_file.raw_write(bytes);
+    T::write_artifacts(e.borrow(), None, test_file);
+    test_file.clear_review();
+}
+
+/// Cleans up after the snapshot matches.
+pub(crate) fn pass_snapshot<T>(actual: &T::Borrowed, test_file: &TestFile)
+where
+    T: Snapshot + ?Sized,
+{
+    T::write_artifacts(actual, None, test_file);
+    test_file.clear_review();
+}
+
+/// Reports the mismatch through the assertion, after writing the snapshot for review.
+pub(crate) fn fail_snapshot<T, F>(
+    mode: SnapshotMode,
+    test_file: &TestFile,
+    actual_bytes: &[u8],
+    actual: &T::Borrowed,
+    expected: &T::Borrowed,
+    assertion: F,
+) -> !
+where
+    T: Snapshot + ?Sized,
+    F: FnOnce(&T::Borrowed, &T::Borrowed),
+{
+    let note = review_note(mode, test_file, actual_bytes);
+    if !note.is_empty() {
+        eprintln!("{}", note.trim_start());
+    }
+    T::write_artifacts(actual, Some(expected), test_file);
+    assertion(actual, expected);
+    unreachable!();
+}
+
+/// The assertion used by [`assert_snapshot!`].
+///
+/// Reports the mismatch with [`Snapshot::mismatch_message`] if available,
+/// like a hexdump for binary snapshots, and with `assert_eq!` otherwise.
+#[doc(hidden)]
+pub fn assert_snapshot_eq<T>(
+    _e: &T,
+    actual: &T::Borrowed,
+    expected: &T::Borrowed,
+    test_file: &TestFile,
+) where
+    T: Snapshot + ?Sized,
+    T::Borrowed: fmt::Debug + PartialEq,
+{
+    if let Some(message) = T::mismatch_message(actual, expected, test_file) {
+        panic!("Snapshot mismatch: {}: {}", test_file, message.trim_end());
+    }
+    pretty_assertions::assert_eq!(*actual, *expected);
+}
+
+/// Writes the actual snapshot for review in [`SnapshotMode::Review`],
+/// and returns the line to append to the panic message.
+pub(crate) fn review_note(mode: SnapshotMode, test_file: &TestFile, actual: &[u8]) -> String {
+    if mode != SnapshotMode::Review {
+        return String::new();
+    }
+    let path = test_file.write_for_review(actual);
+    format!(
+        "\nPending snapshot written to {}; run `cargo testdata review` to accept or reject it",
+        path.display()
+    )
+}
+
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Compares a byte stream against the snapshot chunk by chunk.
+///
+/// Unlike [`assert_snapshot_helper`], neither the actual output nor the
+/// snapshot is loaded into memory as a whole.
+/// The comparison stops at the first differing chunk and reports
+/// the offset of the first differing byte.
+///
+/// The stream is rewound to write the snapshot if needed.
+pub fn assert_snapshot_stream<R>(mut actual: R, test_file: &TestFile)
+where
+    R: Read + Seek,
 {
-    let bytes = e.to_bytes();
-    fixture.raw_write(&bytes);
+    let mode = SnapshotMode::current();
+    let mut expected = match test_file.try_open() {
+        Ok(expected) => expected,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            if mode >= SnapshotMode::New {
+                test_file.raw_write_from(&mut actual);
+                test_file.clear_review();
+                return;
+            }
+            panic!(
+                "Snapshot does not exist: {}{}",
+                test_file.path_for_writing().display(),
+                review_note(mode, test_file, &read_all(&mut actual))
+            );
+        }
+        Err(e) => panic!("{}", e),
+    };
+
+    let mut actual_buf = vec![0; STREAM_CHUNK_SIZE];
+    let mut expected_buf = vec![0; STREAM_CHUNK_SIZE];
+    let mut offset = 0u64;
+    let mismatch = loop {
+        let actual_len = read_chunk(&mut actual, &mut actual_buf).unwrap();
+        let expected_len = read_chunk(&mut expected, &mut expected_buf).unwrap();
+        let common_len = actual_len.min(expected_len);
+        let pos = actual_buf[..common_len]
+            .iter()
+            .zip(&expected_buf[..common_len])
+            .position(|(a, e)| a != e);
+        if let Some(pos) = pos {
+            break Some(format!("differs at byte offset {}", offset + pos as u64));
+        } else if actual_len != expected_len {
+            let (longer, len) = if actual_len > expected_len {
+                ("actual output", expected_len)
+            } else {
+                ("snapshot", actual_len)
+            };
+            break Some(format!(
+                "{} continues after byte offset {}",
+                longer,
+                offset + len as u64
+            ));
+        } else if actual_len == 0 {
+            break None;
+        }
+        offset += actual_len as u64;
+    };
+    drop(expected);
+
+    if let Some(mismatch) = mismatch {
+        if mode == SnapshotMode::All {
+            actual.seek(SeekFrom::Start(0)).unwrap();
+            test_file.raw_write_from(&mut actual);
+            test_file.clear_review();
+            return;
+        }
+        let note = if mode == SnapshotMode::Review {
+            actual.seek(SeekFrom::Start(0)).unwrap();
+            review_note(mode, test_file, &read_all(&mut actual))
+        } else {
+            String::new()
+        };
+        panic!(
+            "Snapshot mismatch: {}: {}{}",
+            test_file.path_for_writing().display(),
+            mismatch,
+            note
+        );
+    }
+    test_file.clear_review();
+}
+
+/// Reads the rest of the stream, only to write it for review.
+fn read_all<R: Read>(reader: &mut R) -> Vec<u8> {
+    let mut contents = Vec::new();
+    reader.read_to_end(&mut contents).unwrap();
+    contents
 }
 
+/// Reads until `buf` is full or the stream ends.
+fn read_chunk<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
+
+/// How the snapshots are updated, configured by the `UPDATE_SNAPSHOTS` environment variable.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum SnapshotMode {
+    /// Never writes snapshots. `UPDATE_SNAPSHOTS=none`, and the default on CI.
     None,
+    /// Writes the actual value next to the snapshot, like `foo-out.txt.new`,
+    /// for `cargo testdata review` to accept or reject. `UPDATE_SNAPSHOTS=review`.
+    Review,
+    /// Writes missing snapshots. `UPDATE_SNAPSHOTS=new`, and the default elsewhere.
     New,
+    /// Overwrites mismatching snapshots. `UPDATE_SNAPSHOTS=all`.
     All,
 }
 
@@ -68,6 +350,8 @@ impl SnapshotMode {
             return SnapshotMode::All;
         } else if update_snapshots == "new" {
             return SnapshotMode::New;
+        } else if update_snapshots == "review" {
+            return SnapshotMode::Review;
         } else if update_snapshots == "none"
             || update_snapshots == "false"
             || update_snapshots == "0"
@@ -87,9 +371,54 @@ pub trait Snapshot {
 
     fn borrow(&self) -> &Self::Borrowed;
     fn to_bytes(&self) -> Vec<u8>;
+
+    /// Reads the snapshot stored in `test_file` to compare with `self`.
+    ///
+    /// Defaults to reading it as a [`TestInput`]. Override this if the way
+    /// `self` was written matters, like the encoding of the text.
+    fn read_snapshot(
+        &self,
+        data: &[u8],
+        test_file: &TestFile,
+    ) -> Result<Self::Owned, <Self::Owned as TestInput>::Err> {
+        read_test_file(data, test_file)
+    }
+
+    /// Describes how the actual value differs from the snapshot stored in `test_file`.
+    ///
+    /// This is used by [`assert_snapshot!`] instead of `assert_eq!` if it returns `Some`.
+    /// A custom assertion given to [`assert_snapshot_helper`] is always used as is.
+    fn mismatch_message(
+        actual: &Self::Borrowed,
+        expected: &Self::Borrowed,
+        test_file: &TestFile,
+    ) -> Option<String> {
+        let _ = (actual, expected, test_file);
+        None
+    }
+
+    /// Updates the files kept next to the snapshot in `test_file` to help inspect a mismatch.
+    ///
+    /// Called with the snapshot on a mismatch, and with `None` once the
+    /// snapshot matches or is written, so that stale files can be removed.
+    fn write_artifacts(
+        actual: &Self::Borrowed,
+        expected: Option<&Self::Borrowed>,
+        test_file: &TestFile,
+    ) {
+        let _ = (actual, expected, test_file);
+    }
+
+    /// Returns the snapshot with the JSON pointer redactions applied to the value.
+    ///
+    /// Implemented by the serde-based formats. Returns `None` if unsupported.
+    fn redact_pointers(&self, redactions: &[Redaction]) -> Option<Vec<u8>> {
+        let _ = redactions;
+        None
+    }
 }
 
-impl<'a, T> Snapshot for &'a T
+impl<T> Snapshot for &T
 where
     T: Snapshot + ?Sized,
 {
@@ -103,9 +432,37 @@ where
     fn to_bytes(&self) -> Vec<u8> {
         <T as Snapshot>::to_bytes(self)
     }
+
+    fn read_snapshot(
+        &self,
+        data: &[u8],
+        test_file: &TestFile,
+    ) -> Result<Self::Owned, <Self::Owned as TestInput>::Err> {
+        <T as Snapshot>::read_snapshot(self, data, test_file)
+    }
+
+    fn mismatch_message(
+        actual: &Self::Borrowed,
+        expected: &Self::Borrowed,
+        test_file: &TestFile,
+    ) -> Option<String> {
+        T::mismatch_message(actual, expected, test_file)
+    }
+
+    fn write_artifacts(
+        actual: &Self::Borrowed,
+        expected: Option<&Self::Borrowed>,
+        test_file: &TestFile,
+    ) {
+        T::write_artifacts(actual, expected, test_file)
+    }
+
+    fn redact_pointers(&self, redactions: &[Redaction]) -> Option<Vec<u8>> {
+        <T as Snapshot>::redact_pointers(self, redactions)
+    }
 }
 
-impl<'a, T> Snapshot for &'a mut T
+impl<T> Snapshot for &mut T
 where
     T: Snapshot + ?Sized,
 {
@@ -119,6 +476,34 @@ where
     fn to_bytes(&self) -> Vec<u8> {
         <T as Snapshot>::to_bytes(self)
     }
+
+    fn read_snapshot(
+        &self,
+        data: &[u8],
+        test_file: &TestFile,
+    ) -> Result<Self::Owned, <Self::Owned as TestInput>::Err> {
+        <T as Snapshot>::read_snapshot(self, data, test_file)
+    }
+
+    fn mismatch_message(
+        actual: &Self::Borrowed,
+        expected: &Self::Borrowed,
+        test_file: &TestFile,
+    ) -> Option<String> {
+        T::mismatch_message(actual, expected, test_file)
+    }
+
+    fn write_artifacts(
+        actual: &Self::Borrowed,
+        expected: Option<&Self::Borrowed>,
+        test_file: &TestFile,
+    ) {
+        T::write_artifacts(actual, expected, test_file)
+    }
+
+    fn redact_pointers(&self, redactions: &[Redaction]) -> Option<Vec<u8>> {
+        <T as Snapshot>::redact_pointers(self, redactions)
+    }
 }
 
 impl Snapshot for [u8] {
@@ -132,6 +517,14 @@ impl Snapshot for [u8] {
     fn to_bytes(&self) -> Vec<u8> {
         self.to_owned()
     }
+
+    fn mismatch_message(
+        actual: &Self::Borrowed,
+        expected: &Self::Borrowed,
+        _test_file: &TestFile,
+    ) -> Option<String> {
+        hexdump_diff(actual, expected)
+    }
 }
 
 impl Snapshot for Vec<u8> {
@@ -145,6 +538,14 @@ impl Snapshot for Vec<u8> {
     fn to_bytes(&self) -> Vec<u8> {
         self.clone()
     }
+
+    fn mismatch_message(
+        actual: &Self::Borrowed,
+        expected: &Self::Borrowed,
+        _test_file: &TestFile,
+    ) -> Option<String> {
+        hexdump_diff(actual, expected)
+    }
 }
 
 impl Snapshot for str {
@@ -1,15 +1,120 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::env;
-use std::fs;
-use std::io;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Cursor, Read};
 use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+use once_cell::sync::Lazy;
+use testdata_rt::{
+    read_review_manifest, review_manifest_path, review_path, whiteout_path, write_review_manifest,
+    Compression, GlobSpec,
+};
+
+use crate::compression::{decoder, encode_to, stored_variant, variants};
+use crate::delimited::{read_part, write_part};
+use crate::lock;
+use crate::markdown::{read_block, write_block};
+
+#[derive(Debug, Clone)]
 pub struct TestFile {
     pub paths: Vec<PathBuf>,
+    context: Option<TestFileContext>,
+}
+
+/// Test files are equal if they have the same paths, whichever test case they belong to.
+impl PartialEq for TestFile {
+    fn eq(&self, other: &Self) -> bool {
+        self.paths == other.paths
+    }
+}
+
+impl Eq for TestFile {}
+
+/// Where a [`TestFile`] came from in the test suite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TestFileContext {
+    spec: Arc<GlobSpec>,
+    stem: String,
+    arg_index: usize,
 }
 
 impl TestFile {
+    /// Creates a test file from the overlay paths, without a test case attached.
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            paths,
+            context: None,
+        }
+    }
+
+    /// Attaches the test case this file belongs to.
+    ///
+    /// `arg_index` is the index in `spec.args`.
+    pub fn with_case(mut self, spec: Arc<GlobSpec>, stem: &str, arg_index: usize) -> Self {
+        self.context = Some(TestFileContext {
+            spec,
+            stem: stem.to_owned(),
+            arg_index,
+        });
+        self
+    }
+
+    /// Returns the stem (the part matched by the wildcard) of the test case.
+    pub fn stem(&self) -> Option<&str> {
+        self.context.as_ref().map(|context| &context.stem[..])
+    }
+
+    /// Returns the name of the function argument this file is passed to.
+    pub fn arg_name(&self) -> Option<&str> {
+        let context = self.context.as_ref()?;
+        context.spec.args[context.arg_index].name.as_deref()
+    }
+
+    /// Returns the slug of the section and the index of the code block,
+    /// if this file is a code block in a Markdown document.
+    pub fn block(&self) -> Option<(&str, usize)> {
+        let context = self.context.as_ref()?;
+        let index = context.spec.args[context.arg_index].block?;
+        let (_, slug) = context.spec.split_stem(&context.stem);
+        Some((slug?, index))
+    }
+
+    /// Returns the slug of the test case and the index of the part,
+    /// if this file is a part of a test case in a file with multiple cases.
+    pub fn part(&self) -> Option<(&str, usize)> {
+        let context = self.context.as_ref()?;
+        let index = context.spec.args[context.arg_index].part?;
+        let (_, slug) = context.spec.split_stem(&context.stem);
+        Some((slug?, index))
+    }
+
+    /// Whether this file is a code block or a part of a test case in a larger file.
+    fn is_embedded(&self) -> bool {
+        self.block().is_some() || self.part().is_some()
+    }
+
+    /// Returns the configuration the file was found with.
+    pub fn spec(&self) -> Option<&GlobSpec> {
+        self.context.as_ref().map(|context| &*context.spec)
+    }
+
+    /// Returns the path relative to the root of the test suite.
+    ///
+    /// This is the path of the layer the file is read from,
+    /// or that of the writable layer if the file does not exist.
+    pub fn relative_path(&self) -> Option<&Path> {
+        let context = self.context.as_ref()?;
+        self.display_path().strip_prefix(&context.spec.root).ok()
+    }
+
+    fn display_path(&self) -> &Path {
+        self.path().unwrap_or_else(|| self.path_for_writing())
+    }
+
     pub fn raw_read(&self) -> Vec<u8> {
         self.try_raw_read().unwrap()
     }
@@ -22,16 +127,108 @@ impl TestFile {
     }
 
     pub fn try_raw_read(&self) -> io::Result<Vec<u8>> {
+        let contents = self.try_read_whole()?;
+        let result = if let Some((slug, index)) = self.block() {
+            read_block(&contents, slug, index)
+        } else if let Some((slug, index)) = self.part() {
+            read_part(&contents, slug, index)
+        } else {
+            return Ok(contents);
+        };
+        result.map_err(|e| io::Error::new(e.kind(), format!("{}: {}", self, e)))
+    }
+
+    /// Reads the whole file, even if it is a code block or a part of a test case.
+    fn try_read_whole(&self) -> io::Result<Vec<u8>> {
+        self.verify_lock()?;
+        self.try_read_with(|path, compression| {
+            if compression.is_none() {
+                return fs::read(path);
+            }
+            let mut contents = Vec::new();
+            decoder(compression, File::open(path)?)?.read_to_end(&mut contents)?;
+            Ok(contents)
+        })
+    }
+
+    /// Opens the file for buffered reading, without loading it into memory.
+    pub fn open(&self) -> Box<dyn BufRead + Send> {
+        self.try_open().unwrap()
+    }
+
+    pub fn try_open(&self) -> io::Result<Box<dyn BufRead + Send>> {
+        if self.is_embedded() {
+            return Ok(Box::new(Cursor::new(self.try_raw_read()?)));
+        }
+        self.verify_lock()?;
+        self.try_read_with(|path, compression| {
+            let file = File::open(path)?;
+            if compression.is_none() {
+                return Ok(Box::new(BufReader::new(file)) as Box<dyn BufRead + Send>);
+            }
+            Ok(Box::new(BufReader::new(decoder(compression, file)?)))
+        })
+    }
+
+    /// Maps the file into memory.
+    ///
+    /// Compressed files cannot be mapped.
+    #[cfg(any(feature = "mmap", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "mmap")))]
+    pub fn mmap(&self) -> memmap2::Mmap {
+        self.try_mmap().unwrap()
+    }
+
+    #[cfg(any(feature = "mmap", all(feature = "__doc_cfg", doc)))]
+    #[cfg_attr(all(feature = "__doc_cfg", doc), doc(cfg(feature = "mmap")))]
+    pub fn try_mmap(&self) -> io::Result<memmap2::Mmap> {
+        if self.is_embedded() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Cannot map a part of a file: {}", self),
+            ));
+        }
+        self.verify_lock()?;
+        self.try_read_with(|path, compression| {
+            let file = File::open(path)?;
+            if compression.is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Cannot map a compressed file: {}", path.display()),
+                ));
+            }
+            // SAFETY: test files are not expected to be modified during the test
+            unsafe { memmap2::Mmap::map(&file) }
+        })
+    }
+
+    /// Checks the lock file if this is an input file.
+    fn verify_lock(&self) -> io::Result<()> {
+        match &self.context {
+            Some(context) if context.spec.args[context.arg_index].input => {
+                lock::verify(&context.spec)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies `f` to the first existing path, possibly compressed.
+    fn try_read_with<T, F>(&self, mut f: F) -> io::Result<T>
+    where
+        F: FnMut(&Path, Option<Compression>) -> io::Result<T>,
+    {
         let mut first_error = None;
-        for path in &self.paths {
-            match fs::read(path) {
-                Err(e) if e.kind() == io::ErrorKind::NotFound => {
-                    if first_error.is_none() {
-                        first_error = Some(e);
+        for (_, path) in self.layers() {
+            for (path, compression) in variants(path) {
+                match f(&path, compression) {
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                        if first_error.is_none() {
+                            first_error = Some(e);
+                        }
+                        continue;
                     }
-                    continue;
+                    result => return result,
                 }
-                result => return result,
             }
         }
         if let Some(first_error) = first_error {
@@ -41,44 +238,258 @@ impl TestFile {
         }
     }
 
+    /// Lists the layers not hidden by a whiteout marker, from the top.
+    fn layers(&self) -> impl Iterator<Item = (usize, &Path)> {
+        let mut hidden = false;
+        self.paths
+            .iter()
+            .map(|path| &**path)
+            .enumerate()
+            .take_while(move |&(_, path)| {
+                let visible = !hidden;
+                hidden = whiteout_path(path).exists();
+                visible
+            })
+    }
+
+    /// Removes the file from the writable layer.
+    ///
+    /// If the file still exists in a lower layer, a whiteout marker
+    /// is created to hide it.
     pub fn remove(&self) {
         self.try_remove().unwrap();
     }
 
     pub fn try_remove(&self) -> io::Result<()> {
-        fs::remove_file(self.path_for_writing())?;
-        for path in &self.paths[1..] {
-            if path.exists() {
-                return Err(io::Error::new(
-                    io::ErrorKind::PermissionDenied,
-                    format!("Cannot remove readonly test file: {}", path.display()),
-                ));
+        if self.is_embedded() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Cannot remove a part of a file: {}", self),
+            ));
+        }
+        let path = self.path_for_writing();
+        let in_lower_layers = self
+            .layers()
+            .skip(1)
+            .any(|(_, path)| stored_variant(path).is_some());
+        let mut first_error = None;
+        let mut removed = false;
+        for (path, _) in variants(path) {
+            match fs::remove_file(path) {
+                Ok(()) => removed = true,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    first_error.get_or_insert(e);
+                }
+                Err(e) => return Err(e),
             }
         }
+        if in_lower_layers {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(whiteout_path(path), b"")?;
+        } else if !removed {
+            return Err(first_error.unwrap());
+        }
         Ok(())
     }
 
+    /// Writes the file to the writable layer.
+    ///
+    /// The file is compressed if the argument is configured so,
+    /// or if the writable layer already has the file compressed.
     pub fn raw_write(&self, contents: &[u8]) {
         self.try_raw_write(contents).unwrap();
     }
 
     pub fn try_raw_write(&self, contents: &[u8]) -> io::Result<()> {
+        if !self.is_embedded() {
+            return self.try_write_whole(contents);
+        }
+        // Rewrites the block or the part in place, while no other test case
+        // in the same file does
+        let lock = path_lock(self.path_for_writing());
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let whole = self.try_read_whole()?;
+        let whole = self.replace_embedded(&whole, contents)?;
+        self.try_write_whole(&whole)
+    }
+
+    /// Replaces the block or the part in the contents of the whole file.
+    fn replace_embedded(&self, whole: &[u8], contents: &[u8]) -> io::Result<Vec<u8>> {
+        let result = if let Some((slug, index)) = self.block() {
+            write_block(whole, slug, index, contents)
+        } else {
+            let (slug, index) = self.part().unwrap();
+            write_part(whole, slug, index, contents)
+        };
+        result.map_err(|e| io::Error::new(e.kind(), format!("{}: {}", self, e)))
+    }
+
+    fn try_write_whole(&self, mut contents: &[u8]) -> io::Result<()> {
+        let (path, compression) = self.prepare_for_writing()?;
+        write_atomic(&path, compression, &mut contents)?;
+        Ok(())
+    }
+
+    pub fn raw_write_from<R: Read>(&self, reader: &mut R) {
+        self.try_raw_write_from(reader).unwrap();
+    }
+
+    pub fn try_raw_write_from<R: Read>(&self, reader: &mut R) -> io::Result<u64> {
+        if self.is_embedded() {
+            let mut contents = Vec::new();
+            reader.read_to_end(&mut contents)?;
+            self.try_raw_write(&contents)?;
+            return Ok(contents.len() as u64);
+        }
+        let (path, compression) = self.prepare_for_writing()?;
+        write_atomic(&path, compression, reader)
+    }
+
+    /// Returns the path to the snapshot pending review, like `foo-out.txt.new`.
+    ///
+    /// For a code block or a part of a test case, this is the pending version of the whole file.
+    pub fn review_path(&self) -> PathBuf {
+        review_path(self.path_for_writing())
+    }
+
+    /// Writes the contents as a snapshot pending review, leaving the file itself as is.
+    ///
+    /// Returns the path written to. The pending snapshot is stored uncompressed.
+    /// For a code block or a part of a test case, it is replaced in the pending version
+    /// of the whole file, so that the other pending changes in the file are kept.
+    pub fn write_for_review(&self, contents: &[u8]) -> PathBuf {
+        self.try_write_for_review(contents).unwrap()
+    }
+
+    pub fn try_write_for_review(&self, contents: &[u8]) -> io::Result<PathBuf> {
+        let review_path = self.review_path();
+        // The other test cases in the same file may be pending too
+        let lock = path_lock(self.path_for_writing());
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let contents = if self.is_embedded() {
+            let whole = match fs::read(&review_path) {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => self.try_read_whole()?,
+                result => result?,
+            };
+            Cow::Owned(self.replace_embedded(&whole, contents)?)
+        } else {
+            Cow::Borrowed(contents)
+        };
+        if let Some(parent) = review_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomic(&review_path, None, &mut &contents[..])?;
+        record_review(&review_path, true)?;
+        Ok(review_path)
+    }
+
+    /// Removes the stale snapshot pending review, if any, as the file itself is up to date.
+    ///
+    /// For a code block or a part of a test case, only its pending change is reverted.
+    pub fn clear_review(&self) {
+        self.try_clear_review().unwrap();
+    }
+
+    pub fn try_clear_review(&self) -> io::Result<()> {
+        let review_path = self.review_path();
+        if !self.is_embedded() {
+            return match fs::remove_file(&review_path) {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+                result => result.and_then(|()| record_review(&review_path, false)),
+            };
+        }
+        let lock = path_lock(self.path_for_writing());
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let pending = match fs::read(&review_path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            result => result?,
+        };
+        let pending = self.replace_embedded(&pending, &self.try_raw_read()?)?;
+        if pending == self.try_read_whole()? {
+            fs::remove_file(&review_path)?;
+            record_review(&review_path, false)
+        } else {
+            write_atomic(&review_path, None, &mut &pending[..])?;
+            Ok(())
+        }
+    }
+
+    /// Copies the file from a lower layer to the writable layer.
+    ///
+    /// Returns the path in the writable layer. Does nothing if the file
+    /// is already there. A compressed file is copied uncompressed.
+    pub fn copy_up(&self) -> &Path {
+        self.try_copy_up().unwrap()
+    }
+
+    pub fn try_copy_up(&self) -> io::Result<&Path> {
         let path = self.path_for_writing();
+        match self.source_layer() {
+            Some(0) => {}
+            Some(_) => {
+                let mut reader = self.try_open()?;
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                io::copy(&mut reader, &mut File::create(path)?)?;
+            }
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Test file does not exist: {}", path.display()),
+                ));
+            }
+        }
+        Ok(path)
+    }
+
+    /// Clears the writable layer and decides where to write.
+    fn prepare_for_writing(&self) -> io::Result<(PathBuf, Option<Compression>)> {
+        let path = self.path_for_writing();
+        let compression = self
+            .context
+            .as_ref()
+            .and_then(|context| context.spec.args[context.arg_index].compression)
+            .or_else(|| stored_variant(path).and_then(|(_, compression)| compression));
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
-        fs::write(path, contents)
+        let mut target = None;
+        for (variant_path, variant_compression) in variants(path) {
+            if variant_compression == compression {
+                target = Some(variant_path);
+                continue;
+            }
+            match fs::remove_file(variant_path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        match fs::remove_file(whiteout_path(path)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        Ok((target.unwrap(), compression))
     }
 
     pub fn exists(&self) -> bool {
-        self.paths.iter().any(|path| path.exists())
+        self.source_layer().is_some()
     }
 
+    /// Returns the path in the layer the file is read from.
+    ///
+    /// The file may actually be stored compressed, like `foo-out.txt.zst`.
     pub fn path(&self) -> Option<&Path> {
-        self.paths
-            .iter()
-            .map(|path| &**path)
-            .find(|&path| path.exists())
+        self.source_layer().map(|layer| &*self.paths[layer])
+    }
+
+    /// Returns the index in `paths` of the layer the file is read from.
+    pub fn source_layer(&self) -> Option<usize> {
+        self.layers()
+            .find(|&(_, path)| stored_variant(path).is_some())
+            .map(|(layer, _)| layer)
     }
 
     pub fn path_for_writing(&self) -> &Path {
@@ -86,6 +497,95 @@ impl TestFile {
     }
 }
 
+impl fmt::Display for TestFile {
+    /// Displays the path relative to the workspace root.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        static WORKSPACE_ROOT: Lazy<Option<PathBuf>> = Lazy::new(find_workspace_root);
+
+        let path = self.display_path();
+        let path = env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_owned());
+        let path = if let Some(root) = &*WORKSPACE_ROOT {
+            path.strip_prefix(root).unwrap_or(&path)
+        } else {
+            &path
+        };
+        let path = path
+            .components()
+            .filter(|c| *c != Component::CurDir)
+            .collect::<PathBuf>();
+        write!(f, "{}", path.display())
+    }
+}
+
+/// Locks per file, for the test cases embedded in the same file.
+static PATH_LOCKS: Lazy<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = Lazy::new(Default::default);
+
+/// Returns the lock to hold while rewriting the file in the writable layer.
+fn path_lock(path: &Path) -> Arc<Mutex<()>> {
+    let mut locks = PATH_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    locks.entry(path.to_owned()).or_default().clone()
+}
+
+/// Adds the snapshot pending review to the manifest of its directory, or removes it.
+pub(crate) fn record_review(review_path: &Path, pending: bool) -> io::Result<()> {
+    let manifest = review_manifest_path(review_path);
+    let lock = path_lock(&manifest);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    let mut names = read_review_manifest(&manifest)?;
+    let name = review_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    let changed = if pending {
+        names.insert(name)
+    } else {
+        names.remove(&name)
+    };
+    if changed {
+        write_review_manifest(&manifest, &names)?;
+    }
+    Ok(())
+}
+
+/// Writes to a temporary file next to `path` and renames it,
+/// so that readers never see a partially written file.
+fn write_atomic<R>(path: &Path, compression: Option<Compression>, reader: &mut R) -> io::Result<u64>
+where
+    R: Read + ?Sized,
+{
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(".testdata-");
+    // Created with the usual permissions rather than private to the user
+    #[cfg(unix)]
+    builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o666));
+    let mut file = builder.tempfile_in(dir)?;
+    let len = encode_to(compression, reader, file.as_file_mut())?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(len)
+}
+
+/// Finds the outermost directory with a `Cargo.toml` declaring `[workspace]`,
+/// or the package root if there is none.
+fn find_workspace_root() -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+    let mut root = None;
+    for dir in cwd.ancestors() {
+        if let Ok(manifest) = fs::read_to_string(dir.join("Cargo.toml")) {
+            if root.is_none() || manifest.lines().any(|line| line.trim() == "[workspace]") {
+                root = Some(dir.to_owned());
+            }
+        }
+    }
+    root
+}
+
 pub fn pending<F>(test_file: &TestFile, f: F)
 where
     F: FnOnce(),
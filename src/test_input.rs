@@ -1,12 +1,35 @@
 use std::convert::Infallible;
 use std::error::Error;
+use std::path::Path;
 use std::str::{self, Utf8Error};
 
+use crate::input_error::{Position, TestInputError};
+use crate::test_files::TestFile;
+
 pub trait TestInput: Sized {
     type Err: Error;
     fn try_read_from(data: &[u8]) -> Result<Self, Self::Err>;
+
+    /// Reads the data of the file at the path.
+    ///
+    /// This is used instead of [`TestInput::try_read_from`] whenever the path is known,
+    /// so the implementation can depend on it, like choosing the format from the extension.
+    /// Defaults to ignoring the path.
+    fn try_read_from_path(data: &[u8], path: &Path) -> Result<Self, Self::Err> {
+        let _ = path;
+        Self::try_read_from(data)
+    }
+
+    /// Reads the data, panicking with a [`TestInputError`] on failure.
     fn read_from(data: &[u8]) -> Self {
-        Self::try_read_from(data).unwrap()
+        Self::try_read_from(data)
+            .unwrap_or_else(|e| panic!("{}", TestInputError::new::<Self>(data, &e)))
+    }
+
+    /// Locates the error from [`TestInput::try_read_from`] in the data, if possible.
+    fn error_position(data: &[u8], err: &Self::Err) -> Option<Position> {
+        let _ = (data, err);
+        None
     }
 }
 
@@ -19,6 +42,13 @@ where
         let value = T::try_read_from(data)?;
         Ok(Box::new(value))
     }
+    fn try_read_from_path(data: &[u8], path: &Path) -> Result<Self, Self::Err> {
+        let value = T::try_read_from_path(data, path)?;
+        Ok(Box::new(value))
+    }
+    fn error_position(data: &[u8], err: &Self::Err) -> Option<Position> {
+        T::error_position(data, err)
+    }
 }
 
 impl TestInput for Vec<u8> {
@@ -34,4 +64,46 @@ impl TestInput for String {
         let s = str::from_utf8(data)?;
         Ok(s.to_owned())
     }
+    fn error_position(_data: &[u8], err: &Self::Err) -> Option<Position> {
+        Some(Position::Offset(err.valid_up_to()))
+    }
+}
+
+/// Converts a test file into a parameter of a test function generated by [`files`](crate::files).
+///
+/// This is implemented for `&TestFile` itself and for any [`TestInput`],
+/// which is read and parsed before the test function is called.
+pub trait FromTestFile<'a>: Sized {
+    fn from_test_file(test_file: &'a TestFile) -> Self;
+}
+
+impl<'a> FromTestFile<'a> for &'a TestFile {
+    fn from_test_file(test_file: &'a TestFile) -> Self {
+        test_file
+    }
+}
+
+impl<'a, T> FromTestFile<'a> for T
+where
+    T: TestInput,
+{
+    fn from_test_file(test_file: &'a TestFile) -> Self {
+        let data = test_file
+            .try_raw_read()
+            .unwrap_or_else(|e| panic!("Cannot read {}: {}", test_file, e));
+        read_test_file(&data, test_file).unwrap_or_else(|e| {
+            panic!(
+                "{}",
+                TestInputError::new::<T>(&data, &e).with_path(test_file.to_string())
+            )
+        })
+    }
+}
+
+/// Reads the data of the test file, passing its path if it exists.
+pub(crate) fn read_test_file<T: TestInput>(data: &[u8], test_file: &TestFile) -> Result<T, T::Err> {
+    match test_file.path() {
+        Some(path) => T::try_read_from_path(data, path),
+        None => T::try_read_from(data),
+    }
 }
@@ -1,6 +1,8 @@
 use std::collections::HashSet;
+use std::fs::{File, FileTimes};
 use std::io;
 use std::path::Path;
+use std::time::SystemTime;
 
 /// An equivalent to the `touch` command.
 ///
@@ -24,11 +26,9 @@ use std::path::Path;
 /// ```
 pub fn touch(path: &Path) -> io::Result<()> {
     // Touch the file containing the test
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-    utime::set_file_times(path, now as i64, now as i64)?;
+    let now = SystemTime::now();
+    let times = FileTimes::new().set_accessed(now).set_modified(now);
+    File::options().write(true).open(path)?.set_times(times)?;
     Ok(())
 }
 
@@ -62,15 +62,15 @@ pub fn diff(set1: &[String], set2: &[String]) -> DiffResult {
     let missing = {
         let set1 = set1.iter().collect::<HashSet<_>>();
         set2.iter()
-            .cloned()
             .filter(|stem| !set1.contains(stem))
+            .cloned()
             .collect::<Vec<_>>()
     };
     let extra = {
         let set2 = set2.iter().collect::<HashSet<_>>();
         set1.iter()
-            .cloned()
             .filter(|stem| !set2.contains(stem))
+            .cloned()
             .collect::<Vec<_>>()
     };
     DiffResult {
@@ -0,0 +1,77 @@
+use testdata::{
+    Lines, ParseErrorKind, Parsed, Position, TestInput, TestInputError, Tokens, Trimmed,
+};
+
+#[test]
+fn test_parsed() {
+    assert_eq!(Parsed::<i64>::read_from(b"  -12\n").0, -12);
+
+    let e = Parsed::<i64>::try_read_from(b"\n  x\n").unwrap_err();
+    assert_eq!(e.offset, 3);
+    assert!(matches!(e.kind, ParseErrorKind::Parse(_)));
+    assert_eq!(e.to_string(), "invalid digit found in string");
+}
+
+#[test]
+fn test_lines() {
+    let lines = Lines::<u32>::read_from(b"1\n 2 \n\n3");
+    assert_eq!(lines.0, vec![1, 2, 3]);
+
+    let data = b"1\n2\n3.5\n";
+    let e = Lines::<u32>::try_read_from(data).unwrap_err();
+    let e = TestInputError::new::<Lines<u32>>(data, &e);
+    assert_eq!((e.line, e.column), (Some(3), Some(1)));
+}
+
+#[test]
+fn test_tokens() {
+    let tokens = Tokens::<f64>::read_from(b"1 2.5\n\t-3\n");
+    assert_eq!(tokens.0, vec![1.0, 2.5, -3.0]);
+
+    let e = Tokens::<f64>::try_read_from(b"1 2.5\n  foo\n").unwrap_err();
+    assert_eq!(e.offset, 8);
+
+    let e = Tokens::<u8>::try_read_from(b"1 \xFF").unwrap_err();
+    assert_eq!(e.offset, 2);
+    assert!(matches!(e.kind, ParseErrorKind::Utf8(_)));
+}
+
+#[test]
+fn test_tuples() {
+    let (a, b, c) = <(u32, String, f64)>::read_from(b"1 foo\n2.5\n");
+    assert_eq!((a, &b[..], c), (1, "foo", 2.5));
+
+    let e = <(u32, u32)>::try_read_from(b"1 2 3").unwrap_err();
+    assert_eq!(e.offset, 4);
+    assert_eq!(e.to_string(), "Expected 2 tokens, found 3");
+
+    let e = <(u32, u32)>::try_read_from(b"1").unwrap_err();
+    assert_eq!(e.offset, 1);
+    assert_eq!(e.to_string(), "Expected 2 tokens, found 1");
+
+    let e = <(u32, u32)>::try_read_from(b"1 x").unwrap_err();
+    assert_eq!(e.offset, 2);
+    // The error of the failing token is kept
+    assert!(matches!(
+        &e.kind,
+        ParseErrorKind::Parse(e) if e.is::<std::num::ParseIntError>()
+    ));
+}
+
+#[test]
+fn test_trimmed() {
+    assert_eq!(Trimmed::<String>::read_from(b"\n  foo\n\n").0, "foo");
+    // Only ASCII whitespace is trimmed, as in Parsed
+    assert_eq!(
+        Trimmed::<String>::read_from("\u{3000}foo ".as_bytes()).0,
+        "\u{3000}foo"
+    );
+    assert!(Parsed::<u32>::try_read_from("\u{3000}1".as_bytes()).is_err());
+
+    let data = b"\n  a\xFF";
+    let e = Trimmed::<String>::try_read_from(data).unwrap_err();
+    assert_eq!(
+        Trimmed::<String>::error_position(data, &e),
+        Some(Position::Offset(4))
+    );
+}
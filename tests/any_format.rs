@@ -0,0 +1,52 @@
+#![cfg(all(feature = "json", feature = "toml", feature = "yaml", feature = "ron"))]
+
+use std::fs;
+use std::panic::catch_unwind;
+use std::path::Path;
+
+use serde_json::{json, Value};
+use testdata::{AnyFormat, AnyFormatError, FromTestFile, TestFile, TestInput};
+
+#[testdata::files(rebuild = "tests/any_format.rs")]
+#[test]
+fn test_any_format(
+    #[glob = "tests/fixtures/config/*.json,tests/fixtures/config/*.toml,tests/fixtures/config/*.yaml,tests/fixtures/config/*.ron"]
+    config: AnyFormat<Value>,
+    #[glob = "tests/fixtures/config/*.json,tests/fixtures/config/*.toml,tests/fixtures/config/*.yaml,tests/fixtures/config/*.ron"]
+    file: &TestFile,
+) {
+    let AnyFormat(config) = config;
+    assert_eq!(
+        config,
+        json!({ "name": file.stem().unwrap(), "port": 8080 })
+    );
+}
+
+#[test]
+fn test_any_format_extensions() {
+    let AnyFormat(value) =
+        AnyFormat::<Value>::try_read_from_path(b"a: [1]\n", Path::new("foo.yml")).unwrap();
+    assert_eq!(value, json!({ "a": [1] }));
+
+    let e = AnyFormat::<Value>::try_read_from_path(b"a = 1\n", Path::new("foo.ini")).unwrap_err();
+    assert!(matches!(e, AnyFormatError::UnknownExtension(_)), "{:?}", e);
+    assert_eq!(
+        e.to_string(),
+        "Unsupported extension for AnyFormat: foo.ini"
+    );
+
+    let e = AnyFormat::<Value>::try_read_from(b"{}").unwrap_err();
+    assert!(matches!(e, AnyFormatError::MissingPath), "{:?}", e);
+}
+
+#[test]
+fn test_any_format_error_position() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("broken.toml");
+    fs::write(&path, "name = \"x\"\nport = \n").unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let e = catch_unwind(|| AnyFormat::<Value>::from_test_file(&test_file)).unwrap_err();
+    let message = e.downcast::<String>().unwrap();
+    assert!(message.contains("broken.toml:2:8: "), "{}", message);
+}
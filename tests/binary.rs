@@ -0,0 +1,72 @@
+use std::fs;
+use std::panic::catch_unwind;
+
+use testdata::{assert_snapshot, Snapshot, TestFile};
+
+fn mismatch(actual: &[u8], expected: &[u8]) -> String {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.bin");
+    fs::write(&path, expected).unwrap();
+    let test_file = TestFile::new(vec![path]);
+    let e = catch_unwind(|| assert_snapshot!(actual, snapshot = test_file)).unwrap_err();
+    let message = e.downcast::<String>().unwrap();
+    let (_, message) = message.split_once("foo-out.bin: ").unwrap();
+    message.to_owned()
+}
+
+#[test]
+fn test_binary_mismatch() {
+    let expected = (0..64u8).collect::<Vec<_>>();
+    let mut actual = expected.clone();
+    actual[40] = 0xff;
+    assert_eq!(
+        mismatch(&actual, &expected),
+        "\
+first difference at byte offset 40 (0x28)
+  offset    expected                                         actual
+  00000000  00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f  00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f
+  00000010  10 11 12 13 14 15 16 17 18 19 1a 1b 1c 1d 1e 1f  10 11 12 13 14 15 16 17 18 19 1a 1b 1c 1d 1e 1f
+! 00000020  20 21 22 23 24 25 26 27 28 29 2a 2b 2c 2d 2e 2f  20 21 22 23 24 25 26 27 ff 29 2a 2b 2c 2d 2e 2f
+  00000030  30 31 32 33 34 35 36 37 38 39 3a 3b 3c 3d 3e 3f  30 31 32 33 34 35 36 37 38 39 3a 3b 3c 3d 3e 3f"
+    );
+}
+
+#[test]
+fn test_binary_length_mismatch() {
+    let expected = b"\x00\x01\x02".to_vec();
+    let actual = b"\x00\x01\x02\x03".to_vec();
+    assert_eq!(
+        mismatch(&actual, &expected),
+        "\
+first difference at byte offset 3 (0x3); expected 3 bytes, got 4 bytes
+  offset    expected                                         actual
+! 00000000  00 01 02                                         00 01 02 03"
+    );
+}
+
+#[test]
+fn test_binary_mismatch_is_truncated() {
+    let expected = vec![0u8; 1024];
+    let actual = vec![1u8; 1024];
+    let message = mismatch(&actual, &expected);
+    assert_eq!(message.lines().count(), 11);
+    assert!(message.ends_with("\n  ..."), "{}", message);
+    let test_file = TestFile::new(vec!["foo-out.bin".into()]);
+    assert!(<[u8]>::mismatch_message(b"foo", b"foo", &test_file).is_none());
+}
+
+#[test]
+fn test_binary_custom_assertion() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.bin");
+    fs::write(&path, b"foo").unwrap();
+    let test_file = TestFile::new(vec![path]);
+    let e = catch_unwind(|| {
+        testdata::assert_snapshot_helper(&b"bar"[..], &test_file, |lhs, rhs| {
+            panic!("custom: {:?} != {:?}", lhs, rhs)
+        })
+    })
+    .unwrap_err();
+    let message = e.downcast::<String>().unwrap();
+    assert_eq!(*message, "custom: [98, 97, 114] != [102, 111, 111]");
+}
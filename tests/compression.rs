@@ -0,0 +1,75 @@
+#![cfg(all(feature = "gzip", feature = "zstd"))]
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+
+use testdata::GlobSpecExt as _;
+use testdata::{assert_snapshot, ArgSpec, Compression, GlobSpec, TestFile};
+
+#[test]
+fn test_write_compressed() {
+    let tmp = tempfile::tempdir().unwrap();
+    let spec = GlobSpec::new()
+        .root(tmp.path())
+        .arg(ArgSpec::new("*-in.txt"))
+        .arg(ArgSpec::new("*-out.txt").compression(Compression::Zstd));
+    fs::write(tmp.path().join("foo-in.txt"), "foo\n").unwrap();
+    let test_files = spec.expand("foo").unwrap();
+
+    test_files[1].raw_write(b"FOO\n");
+    let compressed = fs::read(tmp.path().join("foo-out.txt.zst")).unwrap();
+    assert_ne!(compressed, b"FOO\n");
+    assert!(!tmp.path().join("foo-out.txt").exists());
+    assert!(test_files[1].exists());
+    assert_eq!(test_files[1].raw_read(), b"FOO\n");
+    assert_snapshot!("FOO\n", snapshot = test_files[1]);
+
+    assert_eq!(spec.glob().unwrap(), vec!["foo".to_owned()]);
+}
+
+#[test]
+fn test_keep_compression() {
+    let tmp = tempfile::tempdir().unwrap();
+    let test_file = TestFile::new(vec![tmp.path().join("foo-out.txt")]);
+    fs::write(tmp.path().join("foo-out.txt.gz"), gzip(b"foo\n")).unwrap();
+
+    let mut contents = String::new();
+    test_file.open().read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "foo\n");
+
+    test_file.raw_write(b"bar\n");
+    assert!(!tmp.path().join("foo-out.txt").exists());
+    assert_eq!(test_file.raw_read(), b"bar\n");
+
+    test_file.remove();
+    assert!(!tmp.path().join("foo-out.txt.gz").exists());
+}
+
+#[test]
+fn test_copy_up_decompresses() {
+    let tmp = tempfile::tempdir().unwrap();
+    let test_file = TestFile::new(vec![
+        tmp.path().join("upper/foo-out.txt"),
+        tmp.path().join("lower/foo-out.txt"),
+    ]);
+    fs::create_dir_all(tmp.path().join("lower")).unwrap();
+    fs::write(tmp.path().join("lower/foo-out.txt.gz"), gzip(b"foo\n")).unwrap();
+    assert_eq!(test_file.source_layer(), Some(1));
+
+    let path = test_file.copy_up();
+    assert_eq!(path, Path::new(&tmp.path().join("upper/foo-out.txt")));
+    assert_eq!(fs::read(path).unwrap(), b"foo\n");
+}
+
+fn gzip(contents: &[u8]) -> Vec<u8> {
+    let tmp = tempfile::tempdir().unwrap();
+    let spec = GlobSpec::new()
+        .root(tmp.path())
+        .arg(ArgSpec::new("*.txt").compression(Compression::Gzip));
+    let test_file =
+        TestFile::new(vec![tmp.path().join("tmp.txt")]).with_case(Arc::new(spec), "tmp", 0);
+    test_file.raw_write(contents);
+    fs::read(tmp.path().join("tmp.txt.gz")).unwrap()
+}
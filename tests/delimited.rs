@@ -0,0 +1,107 @@
+use std::fs;
+use std::sync::Arc;
+
+use testdata::GlobSpecExt as _;
+use testdata::{assert_snapshot, ArgSpec, GlobSpec, TestFile};
+
+#[testdata::files(rebuild = "tests/delimited.rs")]
+#[test]
+fn test_delimited(
+    #[glob = "tests/fixtures/corpus/*.txt"]
+    #[part = 0]
+    input: String,
+    #[glob = "tests/fixtures/corpus/*.txt"]
+    #[part = 1]
+    output: &TestFile,
+) {
+    let result = input.to_uppercase();
+    assert_snapshot!(result, snapshot = output);
+}
+
+fn spec(root: &std::path::Path) -> GlobSpec {
+    GlobSpec::new()
+        .root(root)
+        .arg(ArgSpec::new("*.txt").part(0))
+        .arg(ArgSpec::new("*.txt").part(1))
+}
+
+#[test]
+fn test_delimited_read() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(
+        tmp.path().join("corpus.txt"),
+        "=== Foo\n\nfoo\n\n=== Bar\nbar\n---\nBAR\n",
+    )
+    .unwrap();
+    let spec = spec(tmp.path());
+    assert_eq!(
+        spec.glob().unwrap(),
+        vec!["corpus#bar".to_owned(), "corpus#foo".to_owned()]
+    );
+
+    let test_files = spec.expand("corpus#bar").unwrap();
+    assert_eq!(test_files[1].part(), Some(("bar", 1)));
+    assert_eq!(test_files[0].raw_read(), b"bar\n");
+    assert_eq!(test_files[1].raw_read(), b"BAR\n");
+
+    let test_files = spec.expand("corpus#foo").unwrap();
+    assert_eq!(test_files[0].raw_read(), b"foo\n");
+    assert_eq!(test_files[1].raw_read_opt(), None);
+}
+
+#[test]
+fn test_delimited_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("corpus.txt");
+    fs::write(
+        &path,
+        "=== Foo\n\nfoo\n\n---\n\nwrong\n\n=== Bar\nbar\n\n=== Baz\nbaz\n---\nBAZ\n",
+    )
+    .unwrap();
+    let spec = Arc::new(spec(tmp.path()));
+
+    // Replaces only the part, keeping the blank lines around it
+    let test_files = spec.expand("corpus#foo").unwrap();
+    test_files[1].raw_write(b"FOO");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "=== Foo\n\nfoo\n\n---\n\nFOO\n\n=== Bar\nbar\n\n=== Baz\nbaz\n---\nBAZ\n",
+    );
+
+    // Adds a part after the last one
+    let test_files = spec.expand("corpus#bar").unwrap();
+    test_files[1].raw_write(b"BAR\n");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "=== Foo\n\nfoo\n\n---\n\nFOO\n\n=== Bar\nbar\n---\nBAR\n\n=== Baz\nbaz\n---\nBAZ\n",
+    );
+    assert_eq!(test_files[1].raw_read(), b"BAR\n");
+}
+
+#[test]
+fn test_delimited_concurrent_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("corpus.txt");
+    let slugs = (0..8).map(|i| format!("case{}", i)).collect::<Vec<_>>();
+    let src = slugs
+        .iter()
+        .map(|slug| format!("=== {}\n{}\n", slug, slug))
+        .collect::<String>();
+    fs::write(&path, src).unwrap();
+    let spec = Arc::new(spec(tmp.path()));
+
+    // Every test case keeps its own part, whichever writes first
+    std::thread::scope(|s| {
+        for slug in &slugs {
+            let test_files = spec.expand(&format!("corpus#{}", slug)).unwrap();
+            s.spawn(move || test_files[1].raw_write(slug.to_uppercase().as_bytes()));
+        }
+    });
+    for slug in &slugs {
+        let test_files = spec.expand(&format!("corpus#{}", slug)).unwrap();
+        assert_eq!(
+            test_files[1].raw_read(),
+            format!("{}\n", slug.to_uppercase()).as_bytes()
+        );
+    }
+}
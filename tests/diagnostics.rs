@@ -0,0 +1,135 @@
+use std::fs;
+use std::panic::catch_unwind;
+
+use testdata::{
+    assert_diagnostics, parse_annotations, rewrite_annotations, AnnotationError, Diagnostic,
+    TestFile,
+};
+
+/// A toy linter reporting `let` bindings and `todo!()`.
+fn lint(src: &str) -> Vec<(usize, &'static str, String)> {
+    let mut diagnostics = Vec::new();
+    for (i, line) in src.lines().enumerate() {
+        if let Some(rest) = line.trim_start().strip_prefix("let ") {
+            let name = rest.split_whitespace().next().unwrap();
+            diagnostics.push((i + 1, "warning", format!("unused variable: `{}`", name)));
+            diagnostics.push((i + 1, "help", "prefix it with an underscore".to_owned()));
+        }
+        if line.contains("todo!()") {
+            diagnostics.push((i + 1, "error", "`todo!` is not allowed".to_owned()));
+        }
+    }
+    diagnostics
+}
+
+#[testdata::files]
+#[test]
+fn test_lint(#[glob = "tests/fixtures/diagnostics/*.txt"] input: &TestFile) {
+    let src = String::from_utf8(input.raw_read()).unwrap();
+    assert_diagnostics!(lint(&src), source = input);
+}
+
+#[test]
+fn test_parse_annotations() {
+    let src = "\
+foo //~ ERROR a
+//~| NOTE b
+bar
+//~^^^ WARN c
+# baz #~ ERROR d
+//~vv HELP e
+
+qux
+";
+    let annotations = parse_annotations(src, "//~").unwrap();
+    let summary = annotations
+        .iter()
+        .map(|a| (a.comment_line, a.diagnostic.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            (1, Diagnostic::new(1, "ERROR", "a")),
+            (2, Diagnostic::new(1, "NOTE", "b")),
+            (4, Diagnostic::new(1, "WARN", "c")),
+            (6, Diagnostic::new(8, "HELP", "e")),
+        ]
+    );
+
+    let annotations = parse_annotations(src, "#~").unwrap();
+    assert_eq!(annotations[0].diagnostic, Diagnostic::new(5, "ERROR", "d"));
+}
+
+#[test]
+fn test_parse_annotations_errors() {
+    let e = parse_annotations("foo\n//~^^ ERROR\n", "//~").unwrap_err();
+    assert!(matches!(e, AnnotationError::OutOfRange { line: 2 }));
+    let e = parse_annotations("//~| ERROR\n", "//~").unwrap_err();
+    assert!(matches!(e, AnnotationError::NoPrevious { line: 1 }));
+    let e = parse_annotations("foo //~\n", "//~").unwrap_err();
+    assert_eq!(e.to_string(), "Missing level in the annotation at line 1");
+}
+
+#[test]
+fn test_diagnostics_mismatch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("lint.txt");
+    fs::write(
+        &path,
+        "let x = 1; //~ WARNING unused\nfoo(); //~ ERROR undefined\ntodo!();\n",
+    )
+    .unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let e = catch_unwind(|| {
+        assert_diagnostics!(
+            vec![
+                (1, "warning", "unused variable: `x`"),
+                (3, "error", "`todo!`")
+            ],
+            source = test_file
+        )
+    })
+    .unwrap_err();
+    let message = e.downcast_ref::<String>().unwrap();
+    assert!(
+        message.ends_with(
+            "lint.txt\n  unexpected: 3: error `todo!`\n  missing: 2: ERROR undefined (annotated at line 2)"
+        ),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn test_rewrite_annotations() {
+    let src = "\
+fn main() {
+    let x = 1; //~ ERROR stale
+    //~^ NOTE stale
+    todo!()
+}";
+    let diagnostics = [
+        Diagnostic::new(2, "WARNING", "unused variable: `x`"),
+        Diagnostic::new(2, "HELP", "prefix it with an underscore"),
+        Diagnostic::new(4, "ERROR", "`todo!` is not allowed\nsee the docs"),
+        Diagnostic::new(5, "NOTE", "in this function"),
+    ];
+    let rewritten = rewrite_annotations(src, "//~", &diagnostics);
+    assert_eq!(
+        rewritten,
+        "\
+fn main() {
+    let x = 1; //~ WARNING unused variable: `x`
+    //~| HELP prefix it with an underscore
+    todo!() //~ ERROR `todo!` is not allowed
+} //~ NOTE in this function"
+    );
+    // The rewritten annotations refer to the same lines
+    let annotations = parse_annotations(&rewritten, "//~").unwrap();
+    let lines = annotations
+        .iter()
+        .map(|a| a.diagnostic.line)
+        .collect::<Vec<_>>();
+    assert_eq!(lines, vec![2, 2, 4, 5]);
+}
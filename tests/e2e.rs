@@ -3,7 +3,7 @@ use std::process::Command;
 #[test]
 fn test_e2e() {
     let cmd = Command::new("cargo")
-        .args(&["test", "-p", "e2e", "--features", "e2e"])
+        .args(["test", "-p", "e2e", "--features", "e2e"])
         .output()
         .unwrap();
     assert_eq!(cmd.status.code(), Some(101));
@@ -12,80 +12,68 @@ fn test_expand() {
     assert_eq!(
         spec.expand("bar"),
         Some(vec![
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/bar-in.txt")],
-            },
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/bar-out.txt")],
-            },
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/bar-in.txt"
+            )]),
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/bar-out.txt"
+            )]),
         ])
     );
     assert_eq!(
         spec.expand("baz"),
         Some(vec![
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/baz-in.txt")],
-            },
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/baz-out.txt")],
-            },
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/baz-in.txt"
+            )]),
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/baz-out.txt"
+            )]),
         ])
     );
     assert_eq!(
         spec.expand("foo"),
         Some(vec![
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/foo-in.txt")],
-            },
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/foo-out.txt")],
-            },
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/foo-in.txt"
+            )]),
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/foo-out.txt"
+            )]),
         ])
     );
     assert_eq!(spec.expand("fooo"), None);
     assert_eq!(
         spec.expand("nested/bar"),
         Some(vec![
-            TestFile {
-                paths: vec![PathBuf::from(
-                    "tests/fixtures/project1/data/nested/bar-in.txt"
-                )],
-            },
-            TestFile {
-                paths: vec![PathBuf::from(
-                    "tests/fixtures/project1/data/nested/bar-out.txt"
-                )],
-            },
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/nested/bar-in.txt"
+            )]),
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/nested/bar-out.txt"
+            )]),
         ])
     );
     assert_eq!(
         spec.expand("nested/baz"),
         Some(vec![
-            TestFile {
-                paths: vec![PathBuf::from(
-                    "tests/fixtures/project1/data/nested/baz-in.txt"
-                )],
-            },
-            TestFile {
-                paths: vec![PathBuf::from(
-                    "tests/fixtures/project1/data/nested/baz-out.txt"
-                )],
-            },
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/nested/baz-in.txt"
+            )]),
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/nested/baz-out.txt"
+            )]),
         ])
     );
     assert_eq!(
         spec.expand("nested/foo"),
         Some(vec![
-            TestFile {
-                paths: vec![PathBuf::from(
-                    "tests/fixtures/project1/data/nested/foo-in.txt"
-                )],
-            },
-            TestFile {
-                paths: vec![PathBuf::from(
-                    "tests/fixtures/project1/data/nested/foo-out.txt"
-                )],
-            },
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/nested/foo-in.txt"
+            )]),
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/nested/foo-out.txt"
+            )]),
         ])
     );
     if cfg!(windows) {
@@ -115,34 +103,34 @@ fn test_expand_non_nested() {
     assert_eq!(
         spec.expand("bar"),
         Some(vec![
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/bar-in.txt")],
-            },
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/bar-out.txt")],
-            },
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/bar-in.txt"
+            )]),
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/bar-out.txt"
+            )]),
         ])
     );
     assert_eq!(
         spec.expand("baz"),
         Some(vec![
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/baz-in.txt")],
-            },
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/baz-out.txt")],
-            },
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/baz-in.txt"
+            )]),
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/baz-out.txt"
+            )]),
         ])
     );
     assert_eq!(
         spec.expand("foo"),
         Some(vec![
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/foo-in.txt")],
-            },
-            TestFile {
-                paths: vec![PathBuf::from("tests/fixtures/project1/data/foo-out.txt")],
-            },
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/foo-in.txt"
+            )]),
+            TestFile::new(vec![PathBuf::from(
+                "tests/fixtures/project1/data/foo-out.txt"
+            )]),
         ])
     );
     assert_eq!(spec.expand("fooo"), None);
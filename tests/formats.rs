@@ -0,0 +1,105 @@
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml() {
+    use std::collections::HashMap;
+
+    use testdata::{Snapshot, TestInput, TestInputError, Toml};
+
+    let data = b"name = \"foo\"\n\n[sizes]\nb = 2\na = 1\n";
+    let Toml(value) = Toml::<toml::Table>::read_from(data);
+    assert_eq!(value["name"].as_str(), Some("foo"));
+
+    let sizes = [("b", 2), ("a", 1), ("c", 3)]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v))
+        .collect::<HashMap<String, i64>>();
+    let mut root = HashMap::new();
+    root.insert("sizes".to_owned(), sizes);
+    assert_eq!(
+        String::from_utf8(Toml(root).to_bytes()).unwrap(),
+        "[sizes]\na = 1\nb = 2\nc = 3\n"
+    );
+
+    let data = b"name = \"foo\"\nsize = ?\n";
+    let e = Toml::<toml::Table>::try_read_from(data).unwrap_err();
+    let e = TestInputError::new::<Toml<toml::Table>>(data, &e);
+    assert_eq!((e.line, e.column), (Some(2), Some(8)));
+
+    // Invalid UTF-8 is an error rather than replaced
+    let data = b"name = \"fo\xFF\"\n";
+    let e = Toml::<toml::Table>::try_read_from(data).unwrap_err();
+    let e = TestInputError::new::<Toml<toml::Table>>(data, &e);
+    assert_eq!((e.line, e.column), (Some(1), Some(11)));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml() {
+    use std::collections::{BTreeMap, HashMap};
+
+    use testdata::{Snapshot, TestInput, TestInputError, Yaml};
+
+    let Yaml(value) = Yaml::<BTreeMap<String, Vec<u32>>>::read_from(b"a: [1, 2]\nb: []\n");
+    assert_eq!(value["a"], vec![1, 2]);
+
+    let value = [("b", vec![2]), ("a", vec![1]), ("c", vec![])]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v))
+        .collect::<HashMap<String, Vec<u32>>>();
+    assert_eq!(
+        String::from_utf8(Yaml(value).to_bytes()).unwrap(),
+        "a:\n- 1\nb:\n- 2\nc: []\n"
+    );
+
+    let data = b"a: 1\nb: x\n";
+    let e = Yaml::<BTreeMap<String, u32>>::try_read_from(data).unwrap_err();
+    let e = TestInputError::new::<Yaml<BTreeMap<String, u32>>>(data, &e);
+    assert_eq!((e.line, e.column), (Some(2), Some(4)));
+}
+
+#[cfg(feature = "ron")]
+#[test]
+fn test_ron() {
+    use std::collections::{BTreeMap, HashMap};
+
+    use testdata::{Ron, Snapshot, TestInput, TestInputError};
+
+    let Ron(value) = Ron::<BTreeMap<String, (u32, bool)>>::read_from(b"{\"a\": (1, true)}");
+    assert_eq!(value["a"], (1, true));
+
+    let value = [("b", 2), ("a", 1)]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v))
+        .collect::<BTreeMap<String, u32>>();
+    assert_eq!(
+        String::from_utf8(Ron(value).to_bytes()).unwrap(),
+        "{\n    \"a\": 1,\n    \"b\": 2,\n}\n"
+    );
+
+    // The maps are sorted, while the structs keep their fields in order
+    #[derive(serde::Serialize, serde::Deserialize)]
+    enum Shape {
+        Polygon {
+            points: Vec<(i32, i32)>,
+            tags: HashMap<String, u32>,
+        },
+    }
+
+    let tags = [("b", 2), ("c", 3), ("a", 1)]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v))
+        .collect::<HashMap<String, u32>>();
+    let value = Shape::Polygon {
+        points: vec![(0, 0), (1, 2)],
+        tags,
+    };
+    assert_eq!(
+        String::from_utf8(Ron(value).to_bytes()).unwrap(),
+        "Polygon(\n    points: [\n        (0, 0),\n        (1, 2),\n    ],\n    tags: {\n        \"a\": 1,\n        \"b\": 2,\n        \"c\": 3,\n    },\n)\n"
+    );
+
+    let data = b"{\n  \"a\": x,\n}";
+    let e = Ron::<BTreeMap<String, u32>>::try_read_from(data).unwrap_err();
+    let e = TestInputError::new::<Ron<BTreeMap<String, u32>>>(data, &e);
+    assert_eq!(e.line, Some(2));
+}
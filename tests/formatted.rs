@@ -0,0 +1,58 @@
+use std::fs;
+use std::panic::catch_unwind;
+
+use testdata::{
+    assert_debug_snapshot, assert_display_snapshot, DebugSnapshot, DisplaySnapshot, Snapshot,
+    TestFile,
+};
+
+#[derive(Debug)]
+#[allow(dead_code)]
+enum Expr {
+    Num(u32),
+    Add(Box<Expr>, Box<Expr>),
+}
+
+fn ast() -> Expr {
+    Expr::Add(Box::new(Expr::Num(1)), Box::new(Expr::Num(2)))
+}
+
+const AST_DEBUG: &str = "\
+Add(
+    Num(
+        1,
+    ),
+    Num(
+        2,
+    ),
+)
+";
+
+#[test]
+fn test_debug_snapshot() {
+    let snapshot = DebugSnapshot::new(&ast());
+    assert_eq!(snapshot.as_str(), AST_DEBUG);
+    assert_eq!(snapshot.to_bytes(), AST_DEBUG.as_bytes());
+    assert_eq!(format!("{:?}", snapshot), AST_DEBUG);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("ast-out.txt");
+    fs::write(&path, AST_DEBUG).unwrap();
+    let test_file = TestFile::new(vec![path]);
+    assert_debug_snapshot!(ast(), snapshot = test_file);
+
+    let e = catch_unwind(|| assert_debug_snapshot!(Expr::Num(1), snapshot = test_file));
+    assert!(e.is_err());
+}
+
+#[test]
+fn test_display_snapshot() {
+    assert_eq!(DisplaySnapshot::new("foo").as_str(), "foo\n");
+    assert_eq!(DisplaySnapshot::new("foo\n").as_str(), "foo\n");
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("message-out.txt");
+    fs::write(&path, "1 + 2 = 3\n").unwrap();
+    let test_file = TestFile::new(vec![path]);
+    assert_display_snapshot!(format_args!("1 + 2 = {}", 1 + 2), snapshot = test_file);
+}
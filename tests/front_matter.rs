@@ -0,0 +1,73 @@
+use testdata::{FrontMatter, FrontMatterError, Lines, TestInput, TestInputError, Trimmed};
+
+type Case = FrontMatter<Trimmed<String>, Lines<u32>>;
+
+#[test]
+fn test_front_matter_fences() {
+    let case = Case::read_from(b"---\ntitle: foo\n---\n1\n2\n");
+    assert_eq!(case.meta.0, "title: foo");
+    assert_eq!(case.body.0, vec![1, 2]);
+
+    let case = Case::read_from(b"+++\ntitle = \"foo\"\n+++\n3\n");
+    assert_eq!(case.meta.0, "title = \"foo\"");
+    assert_eq!(case.body.0, vec![3]);
+
+    let case = FrontMatter::<Trimmed<String>, String>::read_from(
+        b"// Copyright\n/*---\nflags: [onlyStrict]\n---*/\nfoo();\n",
+    );
+    assert_eq!(case.meta.0, "flags: [onlyStrict]");
+    assert_eq!(
+        case.body,
+        "// Copyright\n/*---\nflags: [onlyStrict]\n---*/\nfoo();\n"
+    );
+}
+
+#[test]
+fn test_front_matter_errors() {
+    let e = Case::try_read_from(b"1\n2\n").unwrap_err();
+    assert!(matches!(e, FrontMatterError::MissingHeader));
+
+    let e = Case::try_read_from(b"\n---\n").unwrap_err();
+    assert!(matches!(e, FrontMatterError::MissingHeader));
+
+    let e = Case::try_read_from(b"---\ntitle: foo\n").unwrap_err();
+    assert!(matches!(e, FrontMatterError::Unterminated { offset: 0 }));
+    assert_eq!(e.to_string(), "Unterminated front matter");
+}
+
+#[test]
+fn test_front_matter_body_position() {
+    let data = b"---\ntitle: foo\n---\n1\nx\n";
+    let e = Case::try_read_from(data).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "Error in the body: invalid digit found in string"
+    );
+    let e = TestInputError::new::<Case>(data, &e);
+    assert_eq!((e.line, e.column), (Some(5), Some(1)));
+}
+
+#[test]
+fn test_front_matter_meta_position() {
+    let data = b"---\n  a\xFF\n---\n";
+    let e = FrontMatter::<Trimmed<String>, String>::try_read_from(data).unwrap_err();
+    let e = TestInputError::new::<FrontMatter<Trimmed<String>, String>>(data, &e);
+    assert_eq!((e.line, e.column), (Some(2), Some(4)));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_front_matter_yaml() {
+    use std::collections::BTreeMap;
+    use testdata::Yaml;
+
+    let data = b"---\nflags: [strict]\nnegative: true\n---\nfoo();\n";
+    let case = FrontMatter::<Yaml<BTreeMap<String, serde_norway::Value>>, String>::read_from(data);
+    assert_eq!(case.meta.0["negative"], serde_norway::Value::Bool(true));
+    assert_eq!(case.body, "foo();\n");
+
+    let data = b"---\nflags: [strict]\nnegative: true\n---\nfoo();\n";
+    let e = FrontMatter::<Yaml<BTreeMap<String, u32>>, String>::try_read_from(data).unwrap_err();
+    let e = TestInputError::new::<FrontMatter<Yaml<BTreeMap<String, u32>>, String>>(data, &e);
+    assert_eq!(e.line, Some(2));
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "image")]
+
+use std::fs;
+use std::panic::catch_unwind;
+
+use testdata::{assert_snapshot, Image, TestFile, TestInput};
+
+fn gradient(width: u32, height: u32) -> Image {
+    let pixels = (0..width * height)
+        .flat_map(|i| [(i % 256) as u8, (i / 256) as u8, 128, 255])
+        .collect();
+    Image::new(width, height, pixels)
+}
+
+#[test]
+fn test_png_roundtrip() {
+    let image = gradient(20, 10);
+    let decoded = Image::read_from(&image.encode_png());
+    assert_eq!((decoded.width, decoded.height), (20, 10));
+    assert_eq!(decoded.pixels, image.pixels);
+}
+
+#[test]
+fn test_image_tolerance() {
+    let image = gradient(10, 10);
+    let mut noisy = image.clone();
+    noisy.pixels[0] += 2;
+    assert_ne!(noisy, image);
+    assert_eq!(noisy.clone().tolerance(2), image);
+
+    noisy.pixels[4] += 10;
+    assert_ne!(noisy.clone().tolerance(2), image);
+    assert_eq!(noisy.clone().tolerance(2).max_diff_ratio(0.01), image);
+    assert_ne!(gradient(10, 9), image);
+}
+
+#[test]
+fn test_image_mismatch_artifacts() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.png");
+    fs::write(&path, gradient(4, 4).encode_png()).unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    assert_snapshot!(gradient(4, 4), snapshot = test_file);
+
+    let mut actual = gradient(4, 4);
+    actual.pixels[0] = 255;
+    let e = catch_unwind(|| assert_snapshot!(actual, snapshot = test_file)).unwrap_err();
+    let message = e.downcast::<String>().unwrap();
+    assert!(
+        message.contains("1 of 16 pixels differ by more than 0 (at most 0 allowed)"),
+        "{}",
+        message
+    );
+
+    let actual_png = Image::read_from(&fs::read(tmp.path().join("foo-out-actual.png")).unwrap());
+    assert_eq!(actual_png.pixels, actual.pixels);
+    let diff_png = Image::read_from(&fs::read(tmp.path().join("foo-out-diff.png")).unwrap());
+    assert_eq!(&diff_png.pixels[..8], &[255, 0, 0, 255, 134, 134, 134, 255]);
+}
+
+#[test]
+fn test_image_artifacts_removed_on_pass() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.png");
+    fs::write(&path, gradient(4, 4).encode_png()).unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let mut actual = gradient(4, 4);
+    actual.pixels[0] = 255;
+    catch_unwind(|| assert_snapshot!(actual, snapshot = test_file)).unwrap_err();
+    assert!(tmp.path().join("foo-out-actual.png").exists());
+    assert!(tmp.path().join("foo-out-diff.png").exists());
+
+    assert_snapshot!(gradient(4, 4), snapshot = test_file);
+    assert!(!tmp.path().join("foo-out-actual.png").exists());
+    assert!(!tmp.path().join("foo-out-diff.png").exists());
+}
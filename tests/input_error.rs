@@ -0,0 +1,93 @@
+use std::panic::catch_unwind;
+
+use testdata::{Position, TestInput, TestInputError};
+
+#[test]
+fn test_string_error_position() {
+    let data = b"foo\nba\xFFr\n";
+    let e = String::try_read_from(data).unwrap_err();
+    assert_eq!(String::error_position(data, &e), Some(Position::Offset(6)));
+
+    let e = TestInputError::new::<String>(data, &e).with_path("tests/fixtures/foo-in.txt");
+    assert_eq!(e.line, Some(2));
+    assert_eq!(e.column, Some(3));
+    assert_eq!(
+        e.to_string(),
+        "Cannot parse tests/fixtures/foo-in.txt:2:3: \
+        invalid utf-8 sequence of 1 bytes from index 6\n  \
+        |\n\
+        2 | ba\u{FFFD}r\n  \
+        |   ^"
+    );
+}
+
+#[test]
+fn test_read_from_panics_with_position() {
+    let e = catch_unwind(|| String::read_from(b"\xFF")).unwrap_err();
+    let message = e.downcast_ref::<String>().unwrap();
+    assert_eq!(
+        message,
+        "Cannot parse input:1:1: invalid utf-8 sequence of 1 bytes from index 0\n  \
+        |\n\
+        1 | \u{FFFD}\n  \
+        | ^"
+    );
+}
+
+#[derive(Debug)]
+struct Number(u32);
+
+impl TestInput for Number {
+    type Err = std::num::ParseIntError;
+    fn try_read_from(data: &[u8]) -> Result<Self, Self::Err> {
+        String::from_utf8_lossy(data).trim().parse().map(Number)
+    }
+}
+
+#[test]
+fn test_no_position() {
+    let e = Number::try_read_from(b"x").unwrap_err();
+    let e = TestInputError::new::<Number>(b"x", &e);
+    assert_eq!(e.line, None);
+    assert_eq!(
+        e.to_string(),
+        "Cannot parse input: invalid digit found in string"
+    );
+    assert_eq!(Number::read_from(b"42\n").0, 42);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_error_position() {
+    use testdata::Json;
+
+    let data = b"{\n  \"a\": ?\n}\n";
+    let e = Json::<serde_json::Value>::try_read_from(data).unwrap_err();
+    let e = TestInputError::new::<Json<serde_json::Value>>(data, &e);
+    assert_eq!(e.line, Some(2));
+    assert_eq!(e.column, Some(8));
+    assert!(
+        e.to_string()
+            .ends_with("\n  |\n2 |   \"a\": ?\n  |        ^"),
+        "{}",
+        e
+    );
+}
+
+#[test]
+fn test_snapshot_error_path() {
+    use testdata::{assert_snapshot, TestFile};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.txt");
+    std::fs::write(&path, b"ok\n\xFF\n").unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let e = catch_unwind(|| {
+        assert_snapshot!(String::from("ok\n"), snapshot = test_file);
+    })
+    .unwrap_err();
+    let message = e.downcast_ref::<String>().unwrap();
+    let prefix = format!("Cannot parse {}:2:1: ", test_file);
+    assert!(message.starts_with(&prefix), "{}", message);
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "json")]
+
+use std::collections::HashMap;
+use std::fs;
+use std::panic::catch_unwind;
+
+use serde_json::{json, Value};
+use testdata::{assert_snapshot, Json, Snapshot, SortedJson, TestFile};
+
+#[test]
+fn test_json_pretty() {
+    let value = json!({ "b": [1, 2], "a": null });
+    assert_eq!(
+        String::from_utf8(Json(value).to_bytes()).unwrap(),
+        "{\n  \"a\": null,\n  \"b\": [\n    1,\n    2\n  ]\n}\n"
+    );
+}
+
+#[test]
+fn test_json_sorted() {
+    let value = [("b", 2), ("c", 3), ("a", 1)]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v))
+        .collect::<HashMap<String, u32>>();
+    assert_eq!(
+        String::from_utf8(SortedJson(value).to_bytes()).unwrap(),
+        "{\n  \"a\": 1,\n  \"b\": 2,\n  \"c\": 3\n}\n"
+    );
+}
+
+#[test]
+fn test_json_semantic_comparison() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.json");
+    fs::write(&path, "{\"b\":[1,2],   \"a\": null}").unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let value = Json(json!({ "a": null, "b": [1, 2] }));
+    assert_snapshot!(value, snapshot = &test_file);
+
+    let value = SortedJson::<Value>(json!({ "a": null, "b": [1, 2] }));
+    assert_snapshot!(value, snapshot = &test_file);
+}
+
+#[test]
+fn test_json_mismatch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.json");
+    fs::write(&path, "{\"a\":[1,2]}").unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let e = catch_unwind(|| {
+        assert_snapshot!(Json(json!({ "a": [1, 3] })), snapshot = &test_file);
+    })
+    .unwrap_err();
+    let message = e.downcast::<String>().unwrap();
+    assert!(
+        message.contains("Diff < actual / snapshot > :"),
+        "{}",
+        message
+    );
+}
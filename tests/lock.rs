@@ -0,0 +1,61 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use testdata::GlobSpecExt as _;
+use testdata::{ArgSpec, GlobSpec, TestFile};
+
+fn input(root: &Path, suite: &str) -> TestFile {
+    let spec = GlobSpec::new()
+        .root(root)
+        .name(suite)
+        .lock_file(&root.join("testdata.lock"))
+        .arg(ArgSpec::new("*-in.txt").input())
+        .arg(ArgSpec::new("*-out.txt"));
+    spec.expand("foo").unwrap().swap_remove(0)
+}
+
+// Kept in one test as it depends on the environment variable.
+#[test]
+fn test_lock() {
+    let tmp = tempfile::tempdir().unwrap();
+    let lock_file = tmp.path().join("testdata.lock");
+    fs::write(tmp.path().join("foo-in.txt"), "foo\n").unwrap();
+
+    // Missing from the lock file
+    let e = input(tmp.path(), "suite1").try_raw_read().unwrap_err();
+    assert!(e.to_string().contains("\n    added: foo-in.txt\n"), "{}", e);
+
+    env::set_var("UPDATE_FIXTURE_LOCK", "1");
+    assert_eq!(input(tmp.path(), "suite2").raw_read(), b"foo\n");
+    env::remove_var("UPDATE_FIXTURE_LOCK");
+    let lock = fs::read_to_string(&lock_file).unwrap();
+    let line = lock.lines().find(|line| !line.starts_with('#')).unwrap();
+    assert_eq!(
+        line,
+        "b5bb9d8014a0f9b1d61e21e796d78dccdf1352f23cd32812f4850b878ae4944c suite2 foo-in.txt",
+    );
+
+    // The output is not locked
+    let spec = input(tmp.path(), "suite2").spec().unwrap().clone();
+    let output = spec.expand("foo").unwrap().swap_remove(1);
+    assert_eq!(output.raw_read_opt(), None);
+
+    fs::write(
+        &lock_file,
+        format!(
+            "{}\n{} suite3 foo-in.txt\n{} suite3 bar-in.txt\n",
+            lock,
+            "0".repeat(64),
+            "0".repeat(64),
+        ),
+    )
+    .unwrap();
+    let e = input(tmp.path(), "suite3").try_raw_read().unwrap_err();
+    assert!(
+        e.to_string()
+            .contains("\n    removed: bar-in.txt\n    modified: foo-in.txt\n"),
+        "{}",
+        e
+    );
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use std::io::Read;
+use std::sync::Arc;
+
+use testdata::GlobSpecExt as _;
+use testdata::{assert_snapshot, ArgSpec, GlobSpec, TestFile};
+
+#[testdata::files(rebuild = "tests/markdown.rs")]
+#[test]
+fn test_markdown(
+    #[glob = "tests/fixtures/markdown/*.md"]
+    #[block = 0]
+    input: String,
+    #[glob = "tests/fixtures/markdown/*.md"]
+    #[block = 1]
+    output: &TestFile,
+) {
+    let result = input.to_uppercase();
+    assert_snapshot!(result, snapshot = output);
+}
+
+fn spec(root: &std::path::Path) -> GlobSpec {
+    GlobSpec::new()
+        .root(root)
+        .arg(ArgSpec::new("*.md").block(0))
+        .arg(ArgSpec::new("*.md").block(1))
+}
+
+#[test]
+fn test_markdown_glob() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(
+        tmp.path().join("spec.md"),
+        "# Foo\n\n```\nfoo\n```\n\n# No blocks\n\n# Bar\n\n```\nbar\n```\n",
+    )
+    .unwrap();
+    let spec = spec(tmp.path());
+    assert_eq!(
+        spec.glob().unwrap(),
+        vec!["spec#bar".to_owned(), "spec#foo".to_owned()]
+    );
+
+    let test_files = spec.expand("spec#foo").unwrap();
+    assert_eq!(test_files[0].block(), Some(("foo", 0)));
+    assert_eq!(test_files[0].raw_read(), b"foo\n");
+    let mut contents = String::new();
+    test_files[0].open().read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "foo\n");
+    assert_eq!(test_files[1].raw_read_opt(), None);
+}
+
+#[test]
+fn test_markdown_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("spec.md");
+    fs::write(
+        &path,
+        "# Foo\n\n```\nfoo\n```\n\n```\nwrong\n```\n\n# Bar\n\n```\n```\n\nText.\n",
+    )
+    .unwrap();
+    let spec = Arc::new(spec(tmp.path()));
+
+    // Replaces the existing block
+    let test_files = spec.expand("spec#foo").unwrap();
+    test_files[1].raw_write(b"FOO\n");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Foo\n\n```\nfoo\n```\n\n```\nFOO\n```\n\n# Bar\n\n```\n```\n\nText.\n",
+    );
+
+    // Adds a block after the last one, with a longer fence if needed
+    let test_files = spec.expand("spec#bar").unwrap();
+    test_files[1].raw_write(b"```\nBAR");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Foo\n\n```\nfoo\n```\n\n```\nFOO\n```\n\n# Bar\n\n```\n```\n\n````\n```\nBAR\n````\n\nText.\n",
+    );
+    assert_eq!(test_files[1].raw_read(), b"```\nBAR\n");
+
+    // Lengthens the fences of the existing block if needed
+    let test_files = spec.expand("spec#foo").unwrap();
+    test_files[1].raw_write(b"```\nFOO\n```\n");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Foo\n\n```\nfoo\n```\n\n````\n```\nFOO\n```\n````\n\n# Bar\n\n```\n```\n\n````\n```\nBAR\n````\n\nText.\n",
+    );
+    assert_eq!(test_files[1].raw_read(), b"```\nFOO\n```\n");
+}
+
+#[test]
+fn test_markdown_missing_section() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("spec.md"), "# Foo\n\n```\nfoo\n```\n").unwrap();
+    let test_file = TestFile::new(vec![tmp.path().join("spec.md")]).with_case(
+        Arc::new(spec(tmp.path())),
+        "spec#bar",
+        0,
+    );
+    let e = test_file.try_raw_read().unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::NotFound);
+    assert!(
+        e.to_string()
+            .ends_with("No section \"bar\" with code blocks"),
+        "{}",
+        e
+    );
+}
+
+#[test]
+fn test_markdown_concurrent_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("spec.md");
+    let slugs = (0..8).map(|i| format!("case{}", i)).collect::<Vec<_>>();
+    let src = slugs
+        .iter()
+        .map(|slug| format!("# {}\n\n```\n{}\n```\n\n", slug, slug))
+        .collect::<String>();
+    fs::write(&path, src).unwrap();
+    let spec = Arc::new(spec(tmp.path()));
+
+    // Every test case keeps its own block, whichever writes first
+    std::thread::scope(|s| {
+        for slug in &slugs {
+            let test_files = spec.expand(&format!("spec#{}", slug)).unwrap();
+            s.spawn(move || test_files[1].raw_write(slug.to_uppercase().as_bytes()));
+        }
+    });
+    for slug in &slugs {
+        let test_files = spec.expand(&format!("spec#{}", slug)).unwrap();
+        assert_eq!(
+            test_files[1].raw_read(),
+            format!("{}\n", slug.to_uppercase()).as_bytes()
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::Path;
+
+use testdata::TestFile;
+
+fn overlay(root: &Path) -> TestFile {
+    TestFile::new(vec![
+        root.join("upper/foo-out.txt"),
+        root.join("middle/foo-out.txt"),
+        root.join("lower/foo-out.txt"),
+    ])
+}
+
+#[test]
+fn test_source_layer() {
+    let tmp = tempfile::tempdir().unwrap();
+    let test_file = overlay(tmp.path());
+    assert_eq!(test_file.source_layer(), None);
+    assert!(!test_file.exists());
+
+    fs::create_dir_all(tmp.path().join("lower")).unwrap();
+    fs::write(tmp.path().join("lower/foo-out.txt"), "lower").unwrap();
+    assert_eq!(test_file.source_layer(), Some(2));
+    assert_eq!(test_file.raw_read(), b"lower");
+
+    test_file.raw_write(b"upper");
+    assert_eq!(test_file.source_layer(), Some(0));
+    assert_eq!(
+        test_file.path(),
+        Some(&*tmp.path().join("upper/foo-out.txt"))
+    );
+    assert_eq!(test_file.raw_read(), b"upper");
+}
+
+#[test]
+fn test_whiteout() {
+    let tmp = tempfile::tempdir().unwrap();
+    let test_file = overlay(tmp.path());
+    fs::create_dir_all(tmp.path().join("middle")).unwrap();
+    fs::create_dir_all(tmp.path().join("lower")).unwrap();
+    fs::write(tmp.path().join("lower/foo-out.txt"), "lower").unwrap();
+    fs::write(tmp.path().join("middle/.wh.foo-out.txt"), "").unwrap();
+
+    assert_eq!(test_file.source_layer(), None);
+    assert_eq!(test_file.raw_read_opt(), None);
+
+    // Writing to the upper layer is not affected
+    test_file.raw_write(b"upper");
+    assert_eq!(test_file.source_layer(), Some(0));
+}
+
+#[test]
+fn test_remove() {
+    let tmp = tempfile::tempdir().unwrap();
+    let test_file = overlay(tmp.path());
+    fs::create_dir_all(tmp.path().join("lower")).unwrap();
+    fs::write(tmp.path().join("lower/foo-out.txt"), "lower").unwrap();
+    test_file.raw_write(b"upper");
+
+    test_file.remove();
+    assert!(tmp.path().join("upper/.wh.foo-out.txt").exists());
+    assert!(tmp.path().join("lower/foo-out.txt").exists());
+    assert!(!test_file.exists());
+
+    // Writing again removes the whiteout marker
+    test_file.raw_write(b"upper");
+    assert!(!tmp.path().join("upper/.wh.foo-out.txt").exists());
+    assert_eq!(test_file.raw_read(), b"upper");
+
+    fs::remove_file(tmp.path().join("lower/foo-out.txt")).unwrap();
+    test_file.remove();
+    assert!(!tmp.path().join("upper/.wh.foo-out.txt").exists());
+    assert!(!test_file.exists());
+    assert!(test_file.try_remove().is_err());
+}
+
+#[test]
+fn test_copy_up() {
+    let tmp = tempfile::tempdir().unwrap();
+    let test_file = overlay(tmp.path());
+    assert!(test_file.try_copy_up().is_err());
+
+    fs::create_dir_all(tmp.path().join("middle")).unwrap();
+    fs::write(tmp.path().join("middle/foo-out.txt"), "middle").unwrap();
+    let path = test_file.copy_up();
+    assert_eq!(path, tmp.path().join("upper/foo-out.txt"));
+    assert_eq!(fs::read(path).unwrap(), b"middle");
+    assert_eq!(test_file.source_layer(), Some(0));
+}
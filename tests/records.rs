@@ -0,0 +1,84 @@
+#![cfg(feature = "json")]
+
+use std::fs;
+use std::panic::catch_unwind;
+use std::path::Path;
+
+use testdata::__rt::record_field;
+use testdata::{RecordSpec, RecordValue};
+
+#[testdata::records(
+    file = "tests/fixtures/records/add.csv",
+    key = "name",
+    rebuild = "tests/records.rs"
+)]
+#[test]
+fn test_add(a: i32, b: i32, sum: i32, note: Option<String>) {
+    assert_eq!(a + b, sum);
+    if let Some(note) = note {
+        assert_eq!(note, "with \"quotes\", and commas");
+    }
+}
+
+#[testdata::records(file = "tests/fixtures/records/words.jsonl")]
+#[test]
+// `Option::is_none_or` needs Rust 1.82
+#[allow(clippy::unnecessary_map_or)]
+fn test_upper(input: String, output: String, tags: Option<Vec<String>>) {
+    assert_eq!(input.to_uppercase(), output);
+    assert!(tags.map_or(true, |tags| !tags.is_empty()));
+}
+
+#[test]
+fn test_record_names() {
+    let spec = RecordSpec::new(Path::new("tests/fixtures/records/add.csv")).key("name");
+    let records = spec.read().unwrap();
+    let names = records.iter().map(|r| &r.name[..]).collect::<Vec<_>>();
+    assert_eq!(names, vec!["zeros", "one plus one", "negative"]);
+    assert_eq!(records[2].line, 4);
+    assert_eq!(
+        records[2].get("a"),
+        Some(&RecordValue::Text("-3".to_owned()))
+    );
+
+    let spec = RecordSpec::new(Path::new("tests/fixtures/records/words.jsonl"));
+    let names = spec
+        .read()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["line_1", "line_2"]);
+}
+
+#[test]
+fn test_record_field_error() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("cases.csv"), "a,b\nx,\n").unwrap();
+    let spec = RecordSpec::new(Path::new("cases.csv")).root(tmp.path());
+    let record = spec.read().unwrap().remove(0);
+
+    assert_eq!(record_field::<Option<i32>>(&spec, &record, "b"), None);
+    assert_eq!(record_field::<Option<i32>>(&spec, &record, "c"), None);
+
+    let e = catch_unwind(|| record_field::<i32>(&spec, &record, "a")).unwrap_err();
+    let message = e.downcast_ref::<String>().unwrap();
+    assert!(
+        message.starts_with("Cannot read field \"a\" of record \"line_2\" at "),
+        "{}",
+        message
+    );
+    assert!(
+        message.ends_with("cases.csv:2: invalid type: string \"x\", expected i32"),
+        "{}",
+        message
+    );
+
+    let e = catch_unwind(|| record_field::<i32>(&spec, &record, "c")).unwrap_err();
+    let message = e.downcast_ref::<String>().unwrap();
+    assert!(
+        message.ends_with("cases.csv:2: Missing field"),
+        "{}",
+        message
+    );
+}
@@ -0,0 +1,255 @@
+#![cfg(any(feature = "regex", feature = "json"))]
+
+use std::fs;
+use std::panic::catch_unwind;
+
+use testdata::{assert_snapshot, Redaction, TestFile};
+
+fn snapshot(tmp: &tempfile::TempDir, contents: &str) -> TestFile {
+    let path = tmp.path().join("foo-out.txt");
+    fs::write(&path, contents).unwrap();
+    TestFile::new(vec![path])
+}
+
+#[cfg(feature = "regex")]
+mod regexes {
+    use std::env;
+
+    use testdata::assert_display_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_redact_builtin() {
+        let tmp = tempfile::tempdir().unwrap();
+        let test_file = snapshot(
+            &tmp,
+            "created [timestamp] by [uuid] in [temp_dir]/work\nlast seen [timestamp]\n",
+        );
+        let actual = format!(
+            "created 2022-09-25T12:34:56.789Z by 67e55044-10b1-426f-9247-bb680e5fe0c8 in {}\nlast seen 2023-01-02 03:04:05+09:00\n",
+            env::temp_dir().join("work").display()
+        );
+        assert_snapshot!(
+            actual,
+            snapshot = &test_file,
+            redact = [
+                Redaction::timestamps(),
+                Redaction::uuids(),
+                Redaction::temp_dir(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redact_regex() {
+        let tmp = tempfile::tempdir().unwrap();
+        let test_file = snapshot(&tmp, "pid=[n] port=[n] $1\n");
+        let actual = "pid=1234 port=8080 $$\n";
+        assert_snapshot!(
+            actual,
+            snapshot = &test_file,
+            redact = [
+                Redaction::regex(r"(\w+)=\d+", "$1=[n]"),
+                Redaction::literal("$$", "$1"),
+            ]
+        );
+        assert!(Redaction::try_regex("(", "").is_err());
+    }
+
+    #[test]
+    fn test_redact_manifest_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let test_file = snapshot(&tmp, "[manifest_dir]/src/lib.rs\n");
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/lib.rs");
+        assert_display_snapshot!(
+            path.display(),
+            snapshot = &test_file,
+            redact = [Redaction::manifest_dir()]
+        );
+    }
+
+    #[test]
+    fn test_redact_mismatch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let test_file = snapshot(&tmp, "at [timestamp]: ok\n");
+        let e = catch_unwind(|| {
+            let actual = "at 2022-09-25T12:34:56Z: failed\n";
+            assert_snapshot!(
+                actual,
+                snapshot = &test_file,
+                redact = [Redaction::timestamps()]
+            );
+        })
+        .unwrap_err();
+        let message = e.downcast::<String>().unwrap();
+        assert!(message.contains("[timestamp]"), "{}", message);
+        assert!(!message.contains("2022"), "{}", message);
+    }
+}
+
+#[cfg(feature = "json")]
+mod pointers {
+    use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
+    use testdata::{Json, Snapshot, SortedJson};
+
+    use super::*;
+
+    #[test]
+    fn test_redact_pointer() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("foo-out.json");
+        fs::write(
+            &path,
+            r#"{"id": "[id]", "items": [{"at": "[at]", "n": 1}, {"at": "[at]", "n": 2}], "a/b": "[x]"}"#,
+        )
+        .unwrap();
+        let test_file = TestFile::new(vec![path]);
+
+        let value = json!({
+            "id": 123,
+            "items": [{ "at": 1, "n": 1 }, { "at": 2, "n": 2 }],
+            "a/b": null,
+        });
+        let redactions = [
+            Redaction::pointer("/id", "[id]"),
+            Redaction::pointer("/items/*/at", "[at]"),
+            Redaction::pointer("/a~1b", "[x]"),
+            Redaction::pointer("/missing/0", "[none]"),
+        ];
+        assert_snapshot!(
+            Json(value.clone()),
+            snapshot = &test_file,
+            redact = redactions
+        );
+        assert_snapshot!(
+            SortedJson::<Value>(value),
+            snapshot = &test_file,
+            redact = redactions
+        );
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Entry {
+        id: u64,
+        name: String,
+    }
+
+    #[test]
+    fn test_redact_pointer_typed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("foo-out.json");
+        let expected = Json(json!({ "id": "[id]", "name": "foo" })).to_bytes();
+        fs::write(&path, expected).unwrap();
+        let test_file = TestFile::new(vec![path]);
+        let redactions = [Redaction::pointer("/id", "[id]")];
+
+        // The redacted snapshot no longer fits the struct, but matches as bytes
+        let entry = Entry {
+            id: 123,
+            name: "foo".to_owned(),
+        };
+        assert_snapshot!(Json(entry), snapshot = &test_file, redact = redactions);
+
+        // The difference is reported in bytes rather than as a parse error
+        let e = catch_unwind(|| {
+            let entry = Entry {
+                id: 456,
+                name: "bar".to_owned(),
+            };
+            assert_snapshot!(Json(entry), snapshot = &test_file, redact = redactions);
+        })
+        .unwrap_err();
+        let message = e.downcast::<String>().unwrap();
+        assert!(
+            message.contains("Diff < actual / snapshot >"),
+            "{}",
+            message
+        );
+        assert!(message.contains("bar"), "{}", message);
+        assert!(!message.contains("456"), "{}", message);
+    }
+
+    #[test]
+    #[should_panic(expected = "JSON pointer redactions are not supported by")]
+    fn test_redact_pointer_unsupported() {
+        let tmp = tempfile::tempdir().unwrap();
+        let test_file = snapshot(&tmp, "");
+        assert_snapshot!(
+            "foo",
+            snapshot = &test_file,
+            redact = [Redaction::pointer("/id", "[id]")]
+        );
+    }
+}
+
+#[cfg(all(feature = "json", feature = "toml"))]
+#[test]
+fn test_redact_pointer_toml() {
+    use std::collections::BTreeMap;
+
+    use testdata::Toml;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.toml");
+    fs::write(&path, "name = \"foo\"\nversion = \"[version]\"\n").unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let value = [
+        ("name", Some("foo")),
+        ("version", Some("1.2.3")),
+        ("license", None),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_owned(), v.map(str::to_owned)))
+    .collect::<BTreeMap<_, _>>();
+    assert_snapshot!(
+        Toml(value),
+        snapshot = &test_file,
+        redact = [Redaction::pointer("/version", "[version]")]
+    );
+}
+
+#[cfg(all(feature = "json", feature = "ron"))]
+#[test]
+fn test_redact_pointer_ron() {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+    use testdata::Ron;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Event {
+        Started {
+            id: String,
+            at: HashMap<String, u64>,
+        },
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.ron");
+    fs::write(
+        &path,
+        "[\n    Started(\n        id: \"[id]\",\n        at: {\n            \"a\": 1,\n            \"b\": \"[time]\",\n        },\n    ),\n]\n",
+    )
+    .unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let at = [("b", 1664100000), ("a", 1)]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v))
+        .collect();
+    let value = vec![Event::Started {
+        id: String::from("67e55044"),
+        at,
+    }];
+    assert_snapshot!(
+        Ron(value),
+        snapshot = &test_file,
+        redact = [
+            Redaction::pointer("/*/Started/id", "[id]"),
+            Redaction::pointer("/0/Started/at/b", "[time]"),
+        ]
+    );
+}
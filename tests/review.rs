@@ -0,0 +1,160 @@
+use std::fs;
+use std::sync::Arc;
+
+use testdata::{ArgSpec, GlobSpec, PendingSnapshot, TestFile, REVIEW_MANIFEST};
+
+#[test]
+fn test_review_write_and_clear() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.txt");
+    fs::write(&path, "old\n").unwrap();
+    let test_file = TestFile::new(vec![path.clone()]);
+
+    let review_path = test_file.write_for_review(b"new\n");
+    assert_eq!(review_path, tmp.path().join("foo-out.txt.new"));
+    assert_eq!(fs::read_to_string(&review_path).unwrap(), "new\n");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "old\n");
+
+    assert_eq!(
+        fs::read_to_string(tmp.path().join(REVIEW_MANIFEST)).unwrap(),
+        "foo-out.txt.new\n"
+    );
+
+    test_file.clear_review();
+    assert!(!review_path.exists());
+    assert!(!tmp.path().join(REVIEW_MANIFEST).exists());
+    // Nothing to clear
+    test_file.clear_review();
+}
+
+#[test]
+fn test_review_embedded() {
+    let tmp = tempfile::tempdir().unwrap();
+    let doc = "# Foo\n\n```\nfoo\n```\n\n# Bar\n\n```\nbar\n```\n";
+    fs::write(tmp.path().join("spec.md"), doc).unwrap();
+    let spec = Arc::new(
+        GlobSpec::new()
+            .root(tmp.path())
+            .arg(ArgSpec::new("*.md").block(0)),
+    );
+    let block = |slug: &str| {
+        TestFile::new(vec![tmp.path().join("spec.md")]).with_case(
+            spec.clone(),
+            &format!("spec#{}", slug),
+            0,
+        )
+    };
+
+    // Pending changes to both blocks are kept in the same file
+    let review_path = block("foo").write_for_review(b"FOO\n");
+    block("bar").write_for_review(b"BAR\n");
+    assert_eq!(review_path, tmp.path().join("spec.md.new"));
+    assert_eq!(
+        fs::read_to_string(&review_path).unwrap(),
+        "# Foo\n\n```\nFOO\n```\n\n# Bar\n\n```\nBAR\n```\n"
+    );
+
+    // Only the passing block is reverted
+    block("foo").clear_review();
+    assert_eq!(
+        fs::read_to_string(&review_path).unwrap(),
+        "# Foo\n\n```\nfoo\n```\n\n# Bar\n\n```\nBAR\n```\n"
+    );
+    block("bar").clear_review();
+    assert!(!review_path.exists());
+    assert_eq!(fs::read_to_string(tmp.path().join("spec.md")).unwrap(), doc);
+}
+
+#[test]
+fn test_pending_snapshots() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::create_dir_all(tmp.path().join("nested")).unwrap();
+    fs::create_dir_all(tmp.path().join("target")).unwrap();
+    fs::write(tmp.path().join("a-out.txt"), "old\n").unwrap();
+    let write_for_review = |path: &str, contents: &[u8]| {
+        TestFile::new(vec![tmp.path().join(path)]).write_for_review(contents);
+    };
+    write_for_review("a-out.txt", b"new\n");
+    write_for_review("nested/b-out.txt", b"added\n");
+    write_for_review("target/c-out.txt", b"ignored\n");
+    // Not written by the tests, like a fixture
+    fs::write(tmp.path().join("d-out.txt.new"), "fixture\n").unwrap();
+
+    let pending = PendingSnapshot::find(tmp.path()).unwrap();
+    let targets = pending.iter().map(|p| &p.target).collect::<Vec<_>>();
+    assert_eq!(
+        targets,
+        vec![
+            &tmp.path().join("a-out.txt"),
+            &tmp.path().join("nested/b-out.txt"),
+        ]
+    );
+
+    let diff = pending[0].diff().unwrap();
+    assert!(
+        diff.starts_with("Diff < snapshot / pending > :\n"),
+        "{}",
+        diff
+    );
+    assert_eq!(pending[1].diff().unwrap(), "New snapshot:\nadded");
+
+    pending[0].accept().unwrap();
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("a-out.txt")).unwrap(),
+        "new\n"
+    );
+    assert!(!pending[0].path.exists());
+
+    pending[1].reject().unwrap();
+    assert!(!pending[1].path.exists());
+    assert!(!pending[1].target.exists());
+
+    assert_eq!(PendingSnapshot::find(tmp.path()).unwrap(), vec![]);
+    assert!(!tmp.path().join(REVIEW_MANIFEST).exists());
+    assert!(tmp.path().join("d-out.txt.new").exists());
+    assert_eq!(PendingSnapshot::new(&tmp.path().join("a-out.txt")), None);
+}
+
+#[test]
+fn test_review_embedded_concurrent() {
+    let tmp = tempfile::tempdir().unwrap();
+    let slugs = (0..8).map(|i| format!("case{}", i)).collect::<Vec<_>>();
+    let doc = slugs
+        .iter()
+        .map(|slug| format!("# {}\n\n```\n{}\n```\n\n", slug, slug))
+        .collect::<String>();
+    fs::write(tmp.path().join("spec.md"), &doc).unwrap();
+    let spec = Arc::new(
+        GlobSpec::new()
+            .root(tmp.path())
+            .arg(ArgSpec::new("*.md").block(0)),
+    );
+    let block = |slug: &str| {
+        TestFile::new(vec![tmp.path().join("spec.md")]).with_case(
+            spec.clone(),
+            &format!("spec#{}", slug),
+            0,
+        )
+    };
+
+    // Every pending change is kept, whichever is written first
+    std::thread::scope(|s| {
+        for slug in &slugs {
+            let test_file = block(slug);
+            s.spawn(move || test_file.write_for_review(slug.to_uppercase().as_bytes()));
+        }
+    });
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("spec.md.new")).unwrap(),
+        doc.replace("```\ncase", "```\nCASE")
+    );
+
+    std::thread::scope(|s| {
+        for slug in &slugs {
+            let test_file = block(slug);
+            s.spawn(move || test_file.clear_review());
+        }
+    });
+    assert!(!tmp.path().join("spec.md.new").exists());
+    assert!(!tmp.path().join(REVIEW_MANIFEST).exists());
+}
@@ -0,0 +1,55 @@
+use std::fs;
+use std::panic::catch_unwind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use testdata::GlobSpecExt as _;
+use testdata::{sandbox, ArgSpec, GlobSpec, Sandbox};
+
+#[test]
+fn test_sandbox_copy() {
+    let spec = GlobSpec::new()
+        .root(Path::new("tests/fixtures/project2"))
+        .arg(ArgSpec::new("*-in.txt"))
+        .arg(ArgSpec::new("*-new.txt"));
+    let test_files = spec.expand("foo").unwrap();
+
+    let mut sandbox = Sandbox::new();
+    let input = sandbox.copy(&test_files[0]);
+    let output = sandbox.copy(&test_files[1]);
+    assert_eq!(input, sandbox.path().join("foo-in.txt"));
+    assert_eq!(fs::read(&input).unwrap(), b"Foo bar\n");
+    assert_eq!(output, sandbox.path().join("foo-new.txt"));
+    assert!(!output.exists());
+    assert_eq!(sandbox.paths(), &[input, output]);
+
+    let root = sandbox.path().to_owned();
+    drop(sandbox);
+    assert!(!root.exists());
+}
+
+#[test]
+fn test_sandbox_kept_on_failure() {
+    let spec = GlobSpec::new()
+        .root(Path::new("tests/fixtures/project2"))
+        .arg(ArgSpec::new("*-in.txt"));
+    let test_files = spec.expand("foo").unwrap();
+
+    let path = Mutex::new(PathBuf::new());
+    let e = catch_unwind(|| {
+        sandbox(&[&test_files[0]], |sandbox| {
+            *path.lock().unwrap() = sandbox.path().to_owned();
+            fs::write(&sandbox.paths()[0], "modified").unwrap();
+            panic!("failure");
+        })
+    })
+    .unwrap_err();
+    let path = path.into_inner().unwrap();
+    // The original message is kept
+    assert_eq!(
+        *e.downcast::<String>().unwrap(),
+        format!("failure\nThe sandbox is kept at: {}", path.display())
+    );
+    assert_eq!(fs::read(path.join("foo-in.txt")).unwrap(), b"modified");
+    fs::remove_dir_all(path).unwrap();
+}
@@ -0,0 +1,95 @@
+use std::error::Error;
+
+use testdata::{Position, SectionError, Sections, TestInput, TestInputError};
+
+#[derive(Debug, TestInput)]
+struct Case {
+    input: String,
+    #[section = "expected-output"]
+    output: Vec<u8>,
+    options: Option<String>,
+}
+
+#[test]
+fn test_derive_sections() {
+    let case = Case::read_from(
+        b"--- input ---\n\
+        foo\n\
+        bar\n\
+        --- expected-output ---\n\
+        FOO\n\
+        --- options ---\n\
+        upper\n",
+    );
+    assert_eq!(case.input, "foo\nbar\n");
+    assert_eq!(case.output, b"FOO\n");
+    assert_eq!(case.options.as_deref(), Some("upper\n"));
+
+    let case = Case::read_from(b"\n--- expected-output ---\n--- input ---\nfoo");
+    assert_eq!(case.input, "foo");
+    assert_eq!(case.output, b"");
+    assert_eq!(case.options, None);
+}
+
+#[test]
+fn test_derive_sections_errors() {
+    let e = Case::try_read_from(b"--- input ---\nfoo\n").unwrap_err();
+    assert_eq!(e.to_string(), "Missing section: \"expected-output\"");
+
+    let e = Case::try_read_from(b"--- input ---\nfoo\n--- output ---\n").unwrap_err();
+    assert_eq!(e.to_string(), "Unknown section \"output\" at line 3");
+
+    let e = Case::try_read_from(b"--- input ---\n--- input ---\n").unwrap_err();
+    assert_eq!(e.to_string(), "Duplicate section \"input\" at line 2");
+
+    let e = Case::try_read_from(b"foo\n--- input ---\n").unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "Expected a section header like `--- input ---` at line 1"
+    );
+
+    let e = Case::try_read_from(b"--- expected-output ---\n\n--- input ---\n\xFF\n").unwrap_err();
+    assert!(matches!(
+        &e,
+        SectionError::Parse { name, line: 3, .. } if name == "input"
+    ));
+    assert_eq!(
+        e.to_string(),
+        "Error in section \"input\" at line 3: invalid utf-8 sequence of 1 bytes from index 0"
+    );
+}
+
+#[test]
+fn test_derive_sections_error_position() {
+    let data = b"--- expected-output ---\n\n--- input ---\nab\xFF\n";
+    let e = Case::try_read_from(data).unwrap_err();
+    assert!(e.source().unwrap().is::<std::str::Utf8Error>());
+    assert_eq!(
+        Case::error_position(data, &e),
+        Some(Position::LineColumn { line: 4, column: 3 })
+    );
+    let message = TestInputError::new::<Case>(data, &e).to_string();
+    assert!(
+        message.starts_with("Cannot parse input:4:3: "),
+        "{}",
+        message
+    );
+
+    let data = b"--- input ---\n--- output ---\n";
+    let e = Case::try_read_from(data).unwrap_err();
+    assert_eq!(
+        Case::error_position(data, &e),
+        Some(Position::LineColumn { line: 2, column: 1 })
+    );
+}
+
+#[test]
+fn test_sections_parse() {
+    let sections = Sections::parse(b"--- a ---\n1\n---  b  ---\n2\n", &["a", "b"]).unwrap();
+    let names = sections
+        .sections
+        .iter()
+        .map(|section| (section.name, section.line, section.data))
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec![("a", 1, &b"1\n"[..]), ("b", 3, &b"2\n"[..])]);
+}
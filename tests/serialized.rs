@@ -0,0 +1,87 @@
+#![cfg(feature = "json")]
+
+use std::fs;
+use std::panic::catch_unwind;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use testdata::{
+    assert_snapshot, Format, Json, JsonFormat, Position, Serialized, Snapshot, TestFile, TestInput,
+};
+
+/// Compact JSON snapshots, diffed as pretty-printed JSON.
+struct CompactJson;
+
+impl Format for CompactJson {
+    type Error = serde_json::Error;
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Self::Error> {
+        serde_json::from_slice(data)
+    }
+
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+        serde_json::to_vec(value).unwrap()
+    }
+
+    fn render<T: Serialize + ?Sized>(value: &T) -> String {
+        serde_json::to_string_pretty(value).unwrap()
+    }
+}
+
+#[test]
+fn test_serialized_round_trip() {
+    let value = Serialized::<CompactJson, Value>::new(json!({ "a": [1, 2] }));
+    assert_eq!(value.to_bytes(), b"{\"a\":[1,2]}");
+    let read = Serialized::<CompactJson, Value>::read_from(&value.to_bytes());
+    assert_eq!(read, value);
+    assert_eq!(read.into_inner(), json!({ "a": [1, 2] }));
+}
+
+#[test]
+fn test_serialized_builtin_format() {
+    let value = json!({ "b": null, "a": [1] });
+    assert_eq!(
+        Serialized::<JsonFormat, _>::new(value.clone()).to_bytes(),
+        Json(value).to_bytes()
+    );
+
+    let e = Serialized::<JsonFormat, Value>::try_read_from(b"{\n  \"a\": ]").unwrap_err();
+    assert_eq!(
+        Serialized::<JsonFormat, Value>::error_position(b"{\n  \"a\": ]", &e),
+        Some(Position::LineColumn { line: 2, column: 8 })
+    );
+}
+
+#[test]
+fn test_serialized_semantic_comparison() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.json");
+    fs::write(&path, "{ \"a\": [1, 2] }\n").unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let value = Serialized::<CompactJson, Value>::new(json!({ "a": [1, 2] }));
+    assert_snapshot!(value, snapshot = &test_file);
+}
+
+#[test]
+fn test_serialized_mismatch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo-out.json");
+    fs::write(&path, "{\"a\":[1,2]}").unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let e = catch_unwind(|| {
+        let value = Serialized::<CompactJson, Value>::new(json!({ "a": [1, 3] }));
+        assert_snapshot!(value, snapshot = &test_file);
+    })
+    .unwrap_err();
+    let message = e.downcast::<String>().unwrap();
+    assert!(
+        message.contains("Diff < actual / snapshot > :"),
+        "{}",
+        message
+    );
+    // The diff is line by line thanks to the pretty rendering
+    assert!(!message.contains("[1,3]"), "{}", message);
+}
@@ -0,0 +1,58 @@
+use std::fs;
+use std::io::{BufRead, Cursor};
+use std::panic::catch_unwind;
+
+use testdata::{assert_snapshot_stream, TestFile};
+
+#[test]
+fn test_open() {
+    let tmp = tempfile::tempdir().unwrap();
+    let upper = tmp.path().join("upper.txt");
+    let lower = tmp.path().join("lower.txt");
+    fs::write(&lower, "foo\nbar\n").unwrap();
+    let test_file = TestFile::new(vec![upper, lower]);
+
+    let lines = test_file
+        .open()
+        .lines()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(lines, vec!["foo".to_owned(), "bar".to_owned()]);
+}
+
+#[test]
+#[cfg(feature = "mmap")]
+fn test_mmap() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo.bin");
+    fs::write(&path, b"\x00\x01\x02").unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    assert_eq!(&test_file.mmap()[..], b"\x00\x01\x02");
+}
+
+#[test]
+fn test_assert_snapshot_stream() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("foo.bin");
+    let data = (0..200_000u32).map(|i| i as u8).collect::<Vec<_>>();
+    fs::write(&path, &data).unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    assert_snapshot_stream(Cursor::new(&data), &test_file);
+
+    let mut changed = data.clone();
+    changed[150_000] ^= 1;
+    let e = catch_unwind(|| assert_snapshot_stream(Cursor::new(&changed), &test_file)).unwrap_err();
+    let e = e.downcast_ref::<String>().unwrap();
+    assert!(e.ends_with(": differs at byte offset 150000"), "{}", e);
+
+    let e = catch_unwind(|| assert_snapshot_stream(Cursor::new(&data[..100_000]), &test_file))
+        .unwrap_err();
+    let e = e.downcast_ref::<String>().unwrap();
+    assert!(
+        e.ends_with(": snapshot continues after byte offset 100000"),
+        "{}",
+        e
+    );
+}
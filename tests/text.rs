@@ -0,0 +1,106 @@
+#![cfg(feature = "encoding")]
+
+use testdata::encoding_rs::{UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use testdata::encodings::{Utf8, Windows1252};
+use testdata::{assert_snapshot, Encoded, Snapshot, TestFile, TestInput, Text};
+
+#[test]
+fn test_read_utf8() {
+    let text = Text::read_from(b"foo\n");
+    assert_eq!(text.text, "foo\n");
+    assert_eq!(text.encoding, UTF_8);
+    assert!(!text.bom);
+
+    let text = Text::read_from(b"\xEF\xBB\xBFfoo\n");
+    assert_eq!(text.text, "foo\n");
+    assert_eq!(text.encoding, UTF_8);
+    assert!(text.bom);
+
+    let e = Text::try_read_from(b"caf\xE9\n").unwrap_err();
+    assert_eq!(e.to_string(), "Malformed UTF-8 sequence in the text");
+}
+
+#[test]
+fn test_read_utf16() {
+    let text = Text::read_from(b"\xFF\xFEf\0o\0o\0");
+    assert_eq!(text.text, "foo");
+    assert_eq!(text.encoding, UTF_16LE);
+    assert!(text.bom);
+
+    let text = Text::read_from(b"\xFE\xFF\0f\0o\0o");
+    assert_eq!(text.text, "foo");
+    assert_eq!(text.encoding, UTF_16BE);
+    assert!(text.bom);
+}
+
+#[test]
+fn test_decode_declared() {
+    let text = Text::decode(b"caf\xE9\n", WINDOWS_1252).unwrap();
+    assert_eq!(text.text, "café\n");
+    assert_eq!(text.encoding, WINDOWS_1252);
+    assert!(!text.bom);
+
+    // BOM takes precedence over the declared encoding
+    let text = Text::decode(b"\xEF\xBB\xBFcaf\xC3\xA9\n", WINDOWS_1252).unwrap();
+    assert_eq!(text.text, "café\n");
+    assert_eq!(text.encoding, UTF_8);
+}
+
+#[test]
+fn test_encode_roundtrip() {
+    let cases: &[&[u8]] = &[
+        b"foo\n",
+        b"\xEF\xBB\xBFfoo\n",
+        b"\xFF\xFEf\0o\0o\0",
+        b"\xFE\xFF\0f\0o\0o",
+    ];
+    for &case in cases {
+        let text = Text::read_from(case);
+        assert_eq!(text.to_bytes(), case, "text = {:?}", text);
+    }
+
+    let text = Text::decode(b"caf\xE9\n", WINDOWS_1252).unwrap();
+    let text = text.with_text(text.to_uppercase());
+    assert_eq!(text.to_bytes(), b"CAF\xC9\n");
+}
+
+#[test]
+#[should_panic(expected = "The text is not representable in windows-1252")]
+fn test_encode_unmappable() {
+    let text = Text::decode(b"", WINDOWS_1252).unwrap();
+    text.with_text("\u{3042}").encode();
+}
+
+#[test]
+fn test_snapshot_roundtrip_declared() {
+    let tmp = tempfile::tempdir().unwrap();
+    let test_file = TestFile::new(vec![tmp.path().join("foo-out.txt")]);
+    let text = Text::decode(b"caf\xE9\n", WINDOWS_1252).unwrap();
+
+    assert_snapshot!(text, snapshot = test_file);
+    assert_eq!(test_file.raw_read(), b"caf\xE9\n");
+    // Read back in the declared encoding
+    assert_snapshot!(text, snapshot = test_file);
+}
+
+#[testdata::files(rebuild = "tests/text.rs")]
+#[test]
+fn test_encoded_files(
+    #[glob = "tests/fixtures/encoded/*-in.txt"] input: Encoded<Windows1252>,
+    #[glob = "tests/fixtures/encoded/*-out.txt"] output: &TestFile,
+) {
+    assert_eq!(input.encoding, WINDOWS_1252);
+    assert!(!input.text.is_ascii());
+    let result = input.with_text(input.to_uppercase());
+    assert_snapshot!(result, snapshot = output);
+}
+
+#[test]
+fn test_encoded_bom() {
+    let input = Encoded::<Windows1252>::read_from(b"\xEF\xBB\xBFcaf\xC3\xA9\n");
+    assert_eq!(input.text, "café\n");
+    assert_eq!(input.encoding, UTF_8);
+
+    let e = Encoded::<Utf8>::try_read_from(b"caf\xE9\n").unwrap_err();
+    assert_eq!(e.to_string(), "Malformed UTF-8 sequence in the text");
+}
@@ -1,8 +1,9 @@
 #![cfg(unix)]
 
-use std::fs;
+use std::fs::{self, File, FileTimes};
 use std::io;
 use std::os::unix::fs::MetadataExt;
+use std::time::{Duration, SystemTime};
 
 use testdata::util::touch;
 
@@ -16,7 +17,9 @@ fn test_touch() -> io::Result<()> {
     let tmp = tempfile::NamedTempFile::new()?;
     let path = tmp.path();
 
-    utime::set_file_times(path, TIME1, TIME1)?;
+    let time1 = SystemTime::UNIX_EPOCH + Duration::from_secs(TIME1 as u64);
+    let times = FileTimes::new().set_accessed(time1).set_modified(time1);
+    File::options().write(true).open(path)?.set_times(times)?;
     let old_metadata = fs::metadata(path)?;
     eprintln!("old_metadata = {:#?}", old_metadata);
 
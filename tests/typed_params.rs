@@ -0,0 +1,31 @@
+use std::fs;
+use std::panic::catch_unwind;
+
+use testdata::{assert_snapshot, FromTestFile, TestFile};
+
+#[testdata::files(rebuild = "tests/typed_params.rs")]
+#[test]
+fn test_typed(
+    #[glob = "tests/fixtures/project2/**/*-in.txt"] input: String,
+    #[glob = "tests/fixtures/project2/**/*-out.txt"] output: &TestFile,
+) {
+    let result = input.to_uppercase();
+    assert_snapshot!(result, snapshot = output);
+}
+
+#[test]
+fn test_parse_error_reports_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("broken-in.txt");
+    fs::write(&path, b"\xFF").unwrap();
+    let test_file = TestFile::new(vec![path]);
+
+    let e = catch_unwind(|| String::from_test_file(&test_file)).unwrap_err();
+    let message = e.downcast_ref::<String>().unwrap();
+    assert!(message.starts_with("Cannot parse "), "{}", message);
+    assert!(
+        message.contains("broken-in.txt:1:1: invalid utf-8"),
+        "{}",
+        message
+    );
+}